@@ -0,0 +1,218 @@
+use crate::board::{empty_indices, place, winner};
+use crate::sign::Sign;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Score of a won position before adjusting for depth
+const WIN_SCORE: i32 = 10;
+
+/// Maximum depth of a game tree, deeper trees grow too large to return
+pub const MAX_TREE_DEPTH: u32 = 4;
+
+/// Position in a game tree along with its minimax evaluation
+#[derive(Serialize)]
+pub struct TreeNode {
+    /// Representation of the board
+    pub board: String,
+
+    /// Index of the cell played to reach the position, None for the root
+    pub index: Option<usize>,
+
+    /// Sign of the side to move
+    pub to_move: Sign,
+
+    /// Minimax score from the perspective of the side to move, see evaluate
+    pub score: i32,
+
+    /// Positions reachable with one move, empty for finished positions and at the depth limit
+    pub children: Vec<TreeNode>,
+}
+
+/// Finds the best move for the sign using minimax
+///
+/// Returns the index of the cell to play, or None if the board has no empty cells or is already won.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - Sign of the side making the move
+pub fn best_move(board: &str, sign: Sign) -> Option<usize> {
+    evaluate(board, sign).map(|(index, _)| index)
+}
+
+/// Finds every move that shares the best minimax score for the sign, see evaluate
+///
+/// Returns the indexes of the cells in order, empty if the board has no empty cells or is already won.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - Sign of the side making the move
+pub fn best_moves(board: &str, sign: Sign) -> Vec<usize> {
+    let ranked = ranked_moves(board, sign);
+    let best = ranked.first().map(|(_, score)| *score);
+    ranked
+        .into_iter()
+        .take_while(|(_, score)| Some(*score) == best)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Evaluates the board for the side to move using minimax
+///
+/// The score is WIN_SCORE for a win, 0 for a draw and -WIN_SCORE for a loss, adjusted towards 0 by
+/// the amount of moves it takes to reach the result.
+///
+/// Returns the index of the best move along with the score of the position, or None if the board
+/// has no empty cells or is already won.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - Sign of the side to move
+pub fn evaluate(board: &str, sign: Sign) -> Option<(usize, i32)> {
+    ranked_moves(board, sign).into_iter().next()
+}
+
+/// Counts the plies left until the game ends when both sides play perfectly
+///
+/// Read from the minimax score, which is WIN_SCORE less the amount of plies to a forced win or loss.
+/// Drawn games only end once the board is full, so a draw takes as many plies as there are empty cells.
+///
+/// Returns None if the board has no empty cells or is already won.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - Sign of the side to move
+pub fn distance_to_end(board: &str, sign: Sign) -> Option<u32> {
+    let (_, score) = evaluate(board, sign)?;
+    let plies = match score {
+        0 => empty_indices(board).len() as i32,
+        _ => WIN_SCORE - score.abs(),
+    };
+    Some(plies as u32)
+}
+
+/// Scores every legal move for the side to move using minimax, see evaluate
+///
+/// Returns the index of each empty cell along with the score of the position after playing it,
+/// best first and moves with equal scores by index. Empty if the board is full or already won.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - Sign of the side to move
+pub fn ranked_moves(board: &str, sign: Sign) -> Vec<(usize, i32)> {
+    if winner(board).is_some() {
+        return vec![];
+    }
+
+    let mut moves: Vec<(usize, i32)> = empty_indices(board)
+        .into_iter()
+        .map(|index| {
+            (
+                index,
+                -minimax(&place(board, index, sign), sign.opponent(), 1),
+            )
+        })
+        .collect();
+    moves.sort_by_key(|(_, score)| Reverse(*score)); // Stable, keeping ties by index
+    moves
+}
+
+/// Builds the game tree from the board up to the given depth
+///
+/// Every node is evaluated with minimax regardless of the depth, so the scores of the deepest nodes
+/// still reflect the best play to the end of the game. Children are ordered by the index of the move.
+/// Scores are cached by board, as the same position is reached through different move orders.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'to_move' - Sign of the side to move
+///
+/// * 'depth' - Amount of moves to expand below the board
+pub fn tree(board: &str, to_move: Sign, depth: u32) -> TreeNode {
+    build_node(board, None, to_move, depth, &mut HashMap::new())
+}
+
+/// Builds a node of the game tree, see tree
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'index' - Index of the cell played to reach the board
+///
+/// * 'to_move' - Sign of the side to move
+///
+/// * 'depth' - Amount of moves to expand below the board
+///
+/// * 'scores' - Scores of the boards evaluated so far
+fn build_node(
+    board: &str,
+    index: Option<usize>,
+    to_move: Sign,
+    depth: u32,
+    scores: &mut HashMap<String, i32>,
+) -> TreeNode {
+    let children = if depth == 0 || winner(board).is_some() {
+        vec![]
+    } else {
+        empty_indices(board)
+            .into_iter()
+            .map(|child| {
+                let next = place(board, child, to_move);
+                build_node(&next, Some(child), to_move.opponent(), depth - 1, scores)
+            })
+            .collect()
+    };
+
+    TreeNode {
+        board: String::from(board),
+        index,
+        to_move,
+        score: *scores
+            .entry(String::from(board))
+            .or_insert_with(|| minimax(board, to_move, 0)),
+        children,
+    }
+}
+
+/// Scores the board from the perspective of the side to move
+///
+/// Wins score higher the sooner they happen and losses score higher the later they happen,
+/// so the computer prefers quick wins and delays losses. Draws score 0.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'to_move' - Sign of the side to move
+///
+/// * 'depth' - Amount of moves made since the searched position
+fn minimax(board: &str, to_move: Sign, depth: i32) -> i32 {
+    // Previous move won the game, which is a loss for the side to move
+    if winner(board).is_some() {
+        return depth - WIN_SCORE;
+    }
+
+    let empty = empty_indices(board);
+    if empty.is_empty() {
+        return 0;
+    }
+
+    empty
+        .into_iter()
+        .map(|index| -minimax(&place(board, index, to_move), to_move.opponent(), depth + 1))
+        .max()
+        .unwrap_or(0)
+}
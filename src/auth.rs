@@ -0,0 +1,85 @@
+use crate::config::Config;
+use rocket::http::{Cookie, CookieJar, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Request, State};
+
+/// Request guard for admin endpoints.
+///
+/// Requests must carry the configured API key in the X-API-Key header or they are rejected with
+/// 401 Unauthorized. When no key is configured every request is rejected, admin endpoints are
+/// disabled rather than left open.
+pub struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = match req.guard::<&State<Config>>().await {
+            Outcome::Success(config) => config,
+            _ => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+
+        match &config.api_key {
+            Some(key) if req.headers().get_one("X-API-Key") == Some(key.as_str()) => {
+                Outcome::Success(Admin)
+            }
+            _ => Outcome::Failure((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Name of the signed cookie carrying the owner token of a browser session
+pub const SESSION_COOKIE: &str = "owner_token";
+
+/// Request guard reading the optional owner token from the X-Owner-Token header, or from the session
+/// cookie when the header is missing.
+///
+/// Never fails, requests with neither carry None. Session cookies that fail the signature check are
+/// ignored.
+pub struct OwnerToken(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for OwnerToken {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = req
+            .headers()
+            .get_one("X-Owner-Token")
+            .filter(|token| !token.is_empty())
+            .map(String::from)
+            .or_else(|| {
+                req.cookies()
+                    .get_private(SESSION_COOKIE)
+                    .map(|cookie| cookie.value().to_string())
+            });
+        Outcome::Success(OwnerToken(token))
+    }
+}
+
+/// Request guard reading the optional move token from the X-Move-Token header.
+///
+/// Never fails, requests without the header carry None.
+pub struct MoveToken(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MoveToken {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = req.headers().get_one("X-Move-Token").map(String::from);
+        Outcome::Success(MoveToken(token))
+    }
+}
+
+/// Starts or refreshes the browser session of the owner by setting the signed session cookie
+///
+/// # Arguments
+///
+/// * 'cookies' - Cookies of the response
+///
+/// * 'token' - Owner token the session identifies
+pub fn start_session(cookies: &CookieJar<'_>, token: &str) {
+    cookies.add_private(Cookie::new(SESSION_COOKIE, token.to_string()));
+}
@@ -0,0 +1,427 @@
+use crate::sign::Sign;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Indexes of the cells that make up each winning line: rows, columns and diagonals
+pub const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// LINES as bitmasks, bit i standing for the cell at index i
+const LINE_MASKS: [u16; 8] = line_masks();
+
+/// Builds LINE_MASKS from LINES
+const fn line_masks() -> [u16; 8] {
+    let mut masks = [0; 8];
+    let mut i = 0;
+    while i < LINES.len() {
+        masks[i] = 1 << LINES[i][0] | 1 << LINES[i][1] | 1 << LINES[i][2];
+        i += 1;
+    }
+    masks
+}
+
+/// Kinds of winning lines
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LineKind {
+    Row,
+    Column,
+    Diagonal,
+}
+
+/// The 8 symmetries of the square as cell permutations, identity first.
+///
+/// A transformed board is built by taking, for each cell i, the cell at permutation[i] of the original.
+/// Rotations are clockwise.
+pub const SYMMETRIES: [(&str, [usize; 9]); 8] = [
+    ("IDENTITY", [0, 1, 2, 3, 4, 5, 6, 7, 8]),
+    ("ROTATE_90", [6, 3, 0, 7, 4, 1, 8, 5, 2]),
+    ("ROTATE_180", [8, 7, 6, 5, 4, 3, 2, 1, 0]),
+    ("ROTATE_270", [2, 5, 8, 1, 4, 7, 0, 3, 6]),
+    ("REFLECT_HORIZONTAL", [2, 1, 0, 5, 4, 3, 8, 7, 6]),
+    ("REFLECT_VERTICAL", [6, 7, 8, 3, 4, 5, 0, 1, 2]),
+    ("REFLECT_DIAGONAL", [0, 3, 6, 1, 4, 7, 2, 5, 8]),
+    ("REFLECT_ANTI_DIAGONAL", [8, 5, 2, 7, 4, 1, 6, 3, 0]),
+];
+
+/// Applies a cell permutation from SYMMETRIES to the board
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'permutation' - Index of the original cell to take for each cell of the new board
+pub fn transform(board: &str, permutation: &[usize; 9]) -> String {
+    let cells = board.as_bytes();
+    permutation.iter().map(|i| cells[*i] as char).collect()
+}
+
+/// Returns the canonical form of the board, the lexicographically smallest of its 8 symmetric
+/// forms, along with the name of the symmetry that produces it.
+///
+/// Equivalent positions share the same canonical form. If several symmetries produce the
+/// canonical form, the first one in SYMMETRIES is reported.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+pub fn canonical(board: &str) -> (String, &'static str) {
+    let mut best = (String::from(board), SYMMETRIES[0].0);
+    for (name, permutation) in SYMMETRIES.iter().skip(1) {
+        let candidate = transform(board, permutation);
+        if candidate < best.0 {
+            best = (candidate, name);
+        }
+    }
+    best
+}
+
+/// Replaces the configured marks in a submitted board with the X and O used internally
+///
+/// The marks are matched case insensitively. With custom marks, X and O themselves are not valid
+/// marks and are replaced with U+FFFD so that the board fails validation.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board with the configured marks
+///
+/// * 'marks' - Marks of the first and second side
+pub fn internal_marks(board: &str, marks: (char, char)) -> String {
+    if marks == ('X', 'O') {
+        return String::from(board);
+    }
+    board
+        .chars()
+        .map(|char| match char {
+            _ if char.eq_ignore_ascii_case(&marks.0) => 'X',
+            _ if char.eq_ignore_ascii_case(&marks.1) => 'O',
+            'X' | 'O' | 'x' | 'o' => char::REPLACEMENT_CHARACTER,
+            _ => char,
+        })
+        .collect()
+}
+
+/// Replaces the X and O used internally with the configured marks
+///
+/// # Arguments
+///
+/// * 'text' - Board or other text containing the internal marks
+///
+/// * 'marks' - Marks of the first and second side
+pub fn custom_marks(text: &str, marks: (char, char)) -> String {
+    text.chars()
+        .map(|char| match char {
+            'X' => marks.0,
+            'O' => marks.1,
+            _ => char,
+        })
+        .collect()
+}
+
+/// Strips whitespace and quotes around a board submitted by a client, e.g. a trailing newline
+///
+/// Boards that are already 9 characters long are returned as they are, as a space at either end
+/// may be an empty cell alias.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board as submitted
+pub fn trim_board(board: &str) -> &str {
+    if board.chars().count() == 9 {
+        return board;
+    }
+    board.trim_matches(|char: char| char.is_whitespace() || char == '"' || char == '\'')
+}
+
+/// Normalizes a board submitted by a client before it is validated
+///
+/// Lowercase 'x' and 'o' are uppercased and any of the configured empty cell aliases
+/// (by default space, '_' and '.') are replaced with '-'. Any other characters are left as they are
+/// so that validation can reject them.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board as submitted
+///
+/// * 'empty_aliases' - Characters accepted in place of '-' for empty cells
+pub fn normalize_board(board: &str, empty_aliases: &str) -> String {
+    board
+        .chars()
+        .map(|char| match char {
+            'x' => 'X',
+            'o' => 'O',
+            _ if empty_aliases.contains(char) => '-',
+            _ => char,
+        })
+        .collect()
+}
+
+/// Returns the indexes of all empty ('-') cells on the board
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+pub fn empty_indices(board: &str) -> Vec<usize> {
+    board
+        .chars()
+        .enumerate()
+        .filter(|(_, char)| *char == '-')
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Counts the distinct positions reachable from the board within the given amount of plies
+///
+/// The board itself is not counted. Finished positions are counted but not played on. As every ply
+/// fills a cell, positions reached after a different amount of plies are always distinct.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'to_move' - Sign of the side to move on the board
+///
+/// * 'depth' - Maximum amount of plies played from the board
+///
+/// * 'symmetric' - Whether positions that are symmetric to each other count as one, see canonical
+pub fn reachable_positions(board: &str, to_move: Sign, depth: u32, symmetric: bool) -> usize {
+    let mut frontier = HashSet::from([String::from(board)]);
+    let mut sign = to_move;
+    let mut count = 0;
+    for _ in 0..depth {
+        frontier = frontier
+            .iter()
+            .filter(|position| winner(position).is_none())
+            .flat_map(|position| {
+                empty_indices(position)
+                    .into_iter()
+                    .map(move |index| place(position, index, sign))
+            })
+            // Symmetric positions have symmetric continuations, so playing on from one form is enough
+            .map(|position| match symmetric {
+                true => canonical(&position).0,
+                false => position,
+            })
+            .collect();
+        count += frontier.len();
+        sign = sign.opponent();
+    }
+    count
+}
+
+/// Returns the sign whose turn it is on the board
+///
+/// The side with fewer signs on the board is to move. When both signs have been placed equally often
+/// the side that made the first move is to move again.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'first' - Sign of the side that made the first move
+pub fn side_to_move(board: &str, first: Sign) -> Sign {
+    let x_count = board.chars().filter(|char| *char == 'X').count();
+    let o_count = board.chars().filter(|char| *char == 'O').count();
+    match x_count.cmp(&o_count) {
+        Ordering::Greater => Sign::O,
+        Ordering::Less => Sign::X,
+        Ordering::Equal => first,
+    }
+}
+
+/// Returns the sign that has completed a line on the board, if any
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+pub fn winner(board: &str) -> Option<Sign> {
+    winning_line(board)
+        .and_then(|line| Sign::try_from(board.as_bytes()[LINES[line][0]] as char).ok())
+}
+
+/// Returns whether the sign has completed a line on the board
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'sign' - The sign
+pub fn has_line(board: &str, sign: Sign) -> bool {
+    let (x, o) = board_to_bitboards(board);
+    let cells = match sign {
+        Sign::X => x,
+        Sign::O => o,
+    };
+    LINE_MASKS.iter().any(|mask| cells & mask == *mask)
+}
+
+/// Returns the position in LINES of the first line completed on the board, if any
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+pub fn winning_line(board: &str) -> Option<usize> {
+    let (x, o) = board_to_bitboards(board);
+    LINE_MASKS
+        .iter()
+        .position(|mask| x & mask == *mask || o & mask == *mask)
+}
+
+/// Converts the board to a pair of bitboards, the cells of X and the cells of O
+///
+/// Bit i of a bitboard is set if the sign is in the cell at index i, so only the lowest 9 bits are used.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+pub fn board_to_bitboards(board: &str) -> (u16, u16) {
+    board
+        .chars()
+        .enumerate()
+        .fold((0, 0), |(x, o), (index, cell)| match cell {
+            'X' => (x | 1 << index, o),
+            'O' => (x, o | 1 << index),
+            _ => (x, o),
+        })
+}
+
+/// Converts a pair of bitboards back to a board, the inverse of board_to_bitboards
+///
+/// Bits above the 9 cells are ignored, and X wins if both bitboards claim the same cell.
+///
+/// # Arguments
+///
+/// * 'x' - Bitboard of the cells of X
+///
+/// * 'o' - Bitboard of the cells of O
+pub fn bitboards_to_board(x: u16, o: u16) -> String {
+    (0..9)
+        .map(|index| match (x >> index & 1, o >> index & 1) {
+            (1, _) => 'X',
+            (_, 1) => 'O',
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Classifies a line as a row, column or diagonal along with its index among lines of that kind
+///
+/// Rows and columns are numbered from the top and the left, diagonal 0 runs from the top left corner
+/// and diagonal 1 from the top right corner.
+///
+/// # Arguments
+///
+/// * 'line' - Position of the line in LINES
+pub fn classify_line(line: usize) -> (LineKind, usize) {
+    match line {
+        0..=2 => (LineKind::Row, line),
+        3..=5 => (LineKind::Column, line - 3),
+        _ => (LineKind::Diagonal, line - 6),
+    }
+}
+
+/// Returns a copy of the board with the sign placed on the cell at index
+///
+/// Does NOT validate the move
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'index' - Index of the cell to place the sign on
+///
+/// * 'sign' - Sign to place
+pub fn place(board: &str, index: usize, sign: Sign) -> String {
+    let mut new_board = String::from(board);
+    new_board.replace_range(index..index + 1, sign.as_str());
+    new_board
+}
+
+/// Returns a copy of the board with the cell emptied
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board
+///
+/// * 'index' - Index of the cell to empty
+pub fn clear(board: &str, index: usize) -> String {
+    let mut new_board = String::from(board);
+    new_board.replace_range(index..index + 1, "-");
+    new_board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Looks up the permutation of a symmetry by name
+    fn symmetry(name: &str) -> &'static [usize; 9] {
+        &SYMMETRIES.iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    #[test]
+    fn rotations_turn_the_board_clockwise() {
+        assert_eq!(transform("XO-------", symmetry("ROTATE_90")), "--X--O---");
+        assert_eq!(transform("XO-------", symmetry("ROTATE_180")), "-------OX");
+        assert_eq!(transform("XO-------", symmetry("ROTATE_270")), "---O--X--");
+    }
+
+    #[test]
+    fn reflections_mirror_the_board() {
+        assert_eq!(
+            transform("XO-------", symmetry("REFLECT_HORIZONTAL")),
+            "-OX------"
+        );
+        assert_eq!(
+            transform("XO-------", symmetry("REFLECT_VERTICAL")),
+            "------XO-"
+        );
+        assert_eq!(
+            transform("XO-------", symmetry("REFLECT_DIAGONAL")),
+            "X--O-----"
+        );
+        assert_eq!(
+            transform("XO-------", symmetry("REFLECT_ANTI_DIAGONAL")),
+            "-----O--X"
+        );
+    }
+
+    #[test]
+    fn canonical_form_is_shared_by_symmetric_boards() {
+        assert_eq!(
+            canonical("XO-------"),
+            (String::from("-------OX"), "ROTATE_180")
+        );
+        assert_eq!(
+            canonical("O-X------"),
+            (String::from("------O-X"), "REFLECT_VERTICAL")
+        );
+        assert_eq!(
+            canonical("-X--O---X"),
+            (String::from("----OXX--"), "ROTATE_90")
+        );
+        for (_, permutation) in SYMMETRIES {
+            let symmetric = transform("-X--O---X", &permutation);
+            assert_eq!(canonical(&symmetric).0, "----OXX--");
+        }
+    }
+
+    #[test]
+    fn bitboards_round_trip() {
+        assert_eq!(board_to_bitboards("X---X---O"), (0b1_0001, 0b1_0000_0000));
+        for board in ["---------", "XO--X---O", "XOXOXOOXO", "XXXXXXXXX"] {
+            let (x, o) = board_to_bitboards(board);
+            assert_eq!(x & o, 0);
+            assert_eq!(bitboards_to_board(x, o), board);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::board::custom_marks;
+use crate::game::{validate_position, GameStatus};
+use crate::sign::Sign;
+use std::fmt;
+
+/// State of a game in a single short line for minimal-bandwidth sync, e.g. "X-O-----O|RUNNING|X"
+///
+/// The board, the status and the side to move are separated by '|'. The side to move is '-' once the
+/// game is over.
+#[derive(Debug, PartialEq)]
+pub struct CompactState {
+    /// Representation of the board
+    pub board: String,
+
+    /// Status of the game
+    pub status: GameStatus,
+
+    /// Sign of the side to move, None once the game is over
+    pub to_move: Option<Sign>,
+}
+
+impl CompactState {
+    /// Writes the compact form with the signs in the board and the side to move replaced by the
+    /// configured marks, the status is written as it is
+    ///
+    /// # Arguments
+    ///
+    /// * 'marks' - Marks of the first and second side
+    pub fn with_marks(&self, marks: (char, char)) -> String {
+        let to_move = self.to_move.map(|sign| sign.as_str()).unwrap_or("-");
+        format!(
+            "{}|{}|{}",
+            custom_marks(&self.board, marks),
+            self.status.as_str(),
+            custom_marks(to_move, marks)
+        )
+    }
+}
+
+impl fmt::Display for CompactState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.with_marks(('X', 'O')))
+    }
+}
+
+impl TryFrom<&str> for CompactState {
+    type Error = ();
+
+    /// Parses the compact form written by Display, using the internal X and O
+    ///
+    /// Fails if a part is malformed, the board is not a legal position, or a side is to move in a
+    /// finished game or no side in a running one
+    fn try_from(string: &str) -> Result<CompactState, ()> {
+        let mut parts = string.trim().split('|');
+        let (board, status, to_move) =
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(board), Some(status), Some(to_move), None) => (board, status, to_move),
+                _ => return Err(()),
+            };
+
+        validate_position(board).map_err(|_| ())?;
+        let status = GameStatus::try_from(status)?;
+        let to_move = match (status, to_move) {
+            (GameStatus::RUNNING, sign) => Some(Sign::try_from(sign)?),
+            (_, "-") => None,
+            _ => return Err(()),
+        };
+
+        Ok(CompactState {
+            board: String::from(board),
+            status,
+            to_move,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_form_round_trips() {
+        let states = [
+            CompactState {
+                board: String::from("X-O-----O"),
+                status: GameStatus::RUNNING,
+                to_move: Some(Sign::X),
+            },
+            CompactState {
+                board: String::from("XXXOO----"),
+                status: GameStatus::XWon,
+                to_move: None,
+            },
+        ];
+        for state in states {
+            let compact = state.to_string();
+            assert_eq!(CompactState::try_from(compact.as_str()), Ok(state));
+        }
+        assert_eq!(
+            CompactState::try_from("X-O-----O|RUNNING|X")
+                .unwrap()
+                .to_string(),
+            "X-O-----O|RUNNING|X"
+        );
+    }
+
+    #[test]
+    fn malformed_compact_forms_are_rejected() {
+        for compact in [
+            "X-O-----O|RUNNING",
+            "X-O-----O|RUNNING|-",
+            "XXXOO----|X_WON|O",
+            "XXXX-----|RUNNING|O",
+            "X-O-----O|LOST|X",
+        ] {
+            assert_eq!(CompactState::try_from(compact), Err(()), "{}", compact);
+        }
+    }
+}
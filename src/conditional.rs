@@ -0,0 +1,36 @@
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+/// Request guard reading the optional If-Match header for optimistic concurrency.
+///
+/// Never fails, requests without the header carry None and match any version.
+pub struct IfMatch(pub Option<String>);
+
+impl IfMatch {
+    /// Returns whether the precondition holds for the current ETag of the resource
+    ///
+    /// The header may list several ETags separated by commas, weak ETags are compared by their value
+    /// and '*' matches any ETag.
+    ///
+    /// # Arguments
+    ///
+    /// * 'etag' - Current ETag of the resource, including the quotes
+    pub fn matches(&self, etag: &str) -> bool {
+        match &self.0 {
+            None => true,
+            Some(header) => header
+                .split(',')
+                .map(|tag| tag.trim())
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfMatch(req.headers().get_one("If-Match").map(String::from)))
+    }
+}
@@ -0,0 +1,236 @@
+use crate::strategy::TieBreak;
+use serde::{Serialize, Serializer};
+use std::env;
+use std::str::FromStr;
+
+/// Upper bound of the artificial computer move delay in milliseconds
+const MAX_COMPUTER_MOVE_DELAY: u64 = 2000;
+
+/// Runtime configuration of the API.
+///
+/// Values are read from environment variables when the server is launched, falling back to
+/// defaults that are suitable for running locally.
+#[derive(Clone, Serialize)]
+pub struct Config {
+    /// Maximum amount of requests a single client may make to rate limited endpoints per window
+    /// Set with RATE_LIMIT, defaults to 60
+    pub rate_limit: u32,
+
+    /// Length of the rate limiting window in seconds
+    /// Set with RATE_LIMIT_WINDOW, defaults to 60
+    pub rate_limit_window: u64,
+
+    /// Whether the server runs behind a proxy that sets X-Real-IP, which then identifies rate limited clients
+    /// instead of the address of the connection
+    /// Set with TRUST_PROXY, defaults to false
+    pub trust_proxy: bool,
+
+    /// Maximum size of a JSON request body in bytes, larger bodies are rejected with 413
+    /// Set with JSON_LIMIT, defaults to 1024
+    pub json_limit: u64,
+
+    /// Board games are created from when POST /games is sent without a body, written like a submitted board
+    /// Set with DEFAULT_BOARD, defaults to an empty board
+    pub default_board: String,
+
+    /// Whether getting an unknown game ID that is a UUID creates a game with that ID from the default board
+    /// instead of answering 404, for front-ends that generate their own IDs
+    /// Set with AUTO_CREATE_GAMES, defaults to false
+    pub auto_create_games: bool,
+
+    /// How the minimax strategy picks between equally good moves: "first", "random" or "positional"
+    /// Set with MINIMAX_TIE_BREAK, defaults to "first"
+    pub minimax_tie_break: TieBreak,
+
+    /// Characters accepted in submitted boards in place of '-' for empty cells
+    /// Set with EMPTY_ALIASES, defaults to space, '_' and '.'
+    pub empty_aliases: String,
+
+    /// Marks of the first and second side, X and O are used internally and swapped for these at the API
+    /// Set with MARKS as two distinct characters, e.g. "AB", defaults to "XO"
+    pub marks: (char, char),
+
+    /// Whether submitted boards must be exactly 9 characters, instead of having surrounding
+    /// whitespace and quotes trimmed
+    /// Set with STRICT_BOARDS, defaults to false
+    pub strict_boards: bool,
+
+    /// Whether successful responses are wrapped in an envelope with the payload under "data" and
+    /// metadata about the response under "meta"
+    /// Set with RESPONSE_ENVELOPE, defaults to false
+    pub response_envelope: bool,
+
+    /// Key required in the X-API-Key header of admin requests
+    /// Set with API_KEY, admin endpoints are disabled when unset
+    #[serde(serialize_with = "redact")]
+    pub api_key: Option<String>,
+
+    /// Key the session cookies are signed and encrypted with, as 64 hex characters or 44 base64 characters
+    /// Set with SESSION_SECRET, a random key is used when unset so sessions don't survive a restart
+    #[serde(serialize_with = "redact")]
+    pub session_secret: Option<String>,
+
+    /// Origins allowed to make cross-origin requests
+    /// Set with CORS_ORIGINS as a comma separated list, no origins are allowed when unset
+    pub cors_origins: Vec<String>,
+
+    /// Whether the transcripts of finished games are logged
+    /// Set with TRANSCRIPT_LOG, defaults to false
+    pub transcript_log: bool,
+
+    /// File the transcripts of finished games are appended to, one JSON object per line
+    /// Set with TRANSCRIPT_FILE, transcripts are not written to a file when unset
+    pub transcript_file: Option<String>,
+
+    /// Maximum amount of running games a single owner token may have, creating more is refused
+    /// Set with MAX_GAMES_PER_OWNER, defaults to 0 which disables the limit
+    pub max_games_per_owner: usize,
+
+    /// Whether finished games are left out of the game listing unless explicitly included
+    /// Set with HIDE_FINISHED_GAMES, defaults to false
+    pub hide_finished_games: bool,
+
+    /// Whether importing an export replaces the whole store instead of merging the games into it
+    /// Set with IMPORT_REPLACE, defaults to false
+    pub import_replace: bool,
+
+    /// Time in milliseconds creating a game or making a move may take before the request is answered
+    /// with 503 and the change is discarded
+    /// Set with REQUEST_TIMEOUT, defaults to 10000, 0 disables the timeout
+    pub request_timeout: u64,
+
+    /// Maximum amount of games kept in the store, creating games is refused once reached
+    /// Set with MAX_GAMES, defaults to 10000
+    pub max_games: usize,
+
+    /// Maximum time in seconds a long-polling request waits for a game to change
+    /// Set with LONG_POLL_TIMEOUT, defaults to 30
+    pub long_poll_timeout: u64,
+
+    /// Artificial delay in milliseconds before the computer replies, to make it appear to think
+    /// Requests the computer doesn't reply to, e.g. illegal moves or moves in analysis games, aren't delayed
+    /// Set with COMPUTER_MOVE_DELAY, defaults to 0 and is capped to 2000
+    pub computer_move_delay: u64,
+
+    /// Seconds the player may take to move before forfeiting the game
+    /// Set with MOVE_TIMEOUT, defaults to 0 which disables the timeout
+    pub move_timeout: u64,
+
+    /// Whether games forfeited by the move timeout end in a draw instead of a computer win
+    /// Set with MOVE_TIMEOUT_DRAW, defaults to false
+    pub move_timeout_draw: bool,
+
+    /// Total thinking time in milliseconds each side may use over a game before forfeiting it
+    /// Set with TIME_BUDGET, defaults to 0 which disables the budget
+    pub time_budget: u64,
+
+    /// Maximum amount of deleted game IDs remembered to answer 410 Gone
+    /// Set with TOMBSTONE_CAPACITY, defaults to 1000
+    pub tombstone_capacity: usize,
+
+    /// How long in seconds a deleted game ID is remembered
+    /// Set with TOMBSTONE_TTL, defaults to 3600
+    pub tombstone_ttl: u64,
+}
+
+impl Config {
+    /// Builds the configuration from environment variables
+    pub fn from_env() -> Config {
+        Config {
+            rate_limit: env_or("RATE_LIMIT", 60),
+            rate_limit_window: env_or("RATE_LIMIT_WINDOW", 60),
+            trust_proxy: env_or("TRUST_PROXY", false),
+            json_limit: env_or("JSON_LIMIT", 1024),
+            empty_aliases: env_or("EMPTY_ALIASES", String::from(" _.")),
+            default_board: env_or("DEFAULT_BOARD", String::from("---------")),
+            auto_create_games: env_or("AUTO_CREATE_GAMES", false),
+            minimax_tie_break: env_or("MINIMAX_TIE_BREAK", TieBreak::First),
+            strict_boards: env_or("STRICT_BOARDS", false),
+            response_envelope: env_or("RESPONSE_ENVELOPE", false),
+            marks: parse_marks(
+                &env::var("MARKS").unwrap_or_default(),
+                &env_or("EMPTY_ALIASES", String::from(" _.")),
+            ),
+            max_games: env_or("MAX_GAMES", 10000),
+            request_timeout: env_or("REQUEST_TIMEOUT", 10000),
+            max_games_per_owner: env_or("MAX_GAMES_PER_OWNER", 0),
+            hide_finished_games: env_or("HIDE_FINISHED_GAMES", false),
+            import_replace: env_or("IMPORT_REPLACE", false),
+            long_poll_timeout: env_or("LONG_POLL_TIMEOUT", 30),
+            tombstone_capacity: env_or("TOMBSTONE_CAPACITY", 1000),
+            tombstone_ttl: env_or("TOMBSTONE_TTL", 3600),
+            computer_move_delay: env_or("COMPUTER_MOVE_DELAY", 0).min(MAX_COMPUTER_MOVE_DELAY),
+            move_timeout: env_or("MOVE_TIMEOUT", 0),
+            move_timeout_draw: env_or("MOVE_TIMEOUT_DRAW", false),
+            time_budget: env_or("TIME_BUDGET", 0),
+            api_key: env::var("API_KEY").ok().filter(|key| !key.is_empty()),
+            session_secret: env::var("SESSION_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            transcript_log: env_or("TRANSCRIPT_LOG", false),
+            transcript_file: env::var("TRANSCRIPT_FILE")
+                .ok()
+                .filter(|path| !path.is_empty()),
+            cors_origins: env::var("CORS_ORIGINS")
+                .map(|origins| {
+                    origins
+                        .split(',')
+                        .map(|origin| origin.trim().to_string())
+                        .filter(|origin| !origin.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Parses the marks of both sides, falling back to X and O if they are not two distinct characters
+/// that can't be mistaken for an empty cell
+///
+/// # Arguments
+///
+/// * 'value' - The marks as configured, e.g. "AB"
+///
+/// * 'empty_aliases' - Characters accepted for an empty cell in addition to '-'
+fn parse_marks(value: &str, empty_aliases: &str) -> (char, char) {
+    let marks: Vec<char> = value.trim().chars().collect();
+    match marks[..] {
+        [first, second]
+            if first != second
+                && [first, second]
+                    .iter()
+                    .all(|mark| *mark != '-' && !empty_aliases.contains(*mark)) =>
+        {
+            (first, second)
+        }
+        _ => ('X', 'O'),
+    }
+}
+
+/// Serializes a secret without revealing it, as "[redacted]" when it is set and null otherwise
+///
+/// # Arguments
+///
+/// * 'secret' - The secret
+///
+/// * 'serializer' - Serializer the secret is written to
+fn redact<S: Serializer>(secret: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(_) => serializer.serialize_str("[redacted]"),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Reads and parses an environment variable, returning the default if it is unset or invalid
+///
+/// # Arguments
+///
+/// * 'key' - Name of the environment variable
+///
+/// * 'default' - Value used when the variable is missing or can't be parsed
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(value) => value.trim().parse().unwrap_or(default),
+        Err(_) => default,
+    }
+}
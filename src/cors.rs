@@ -0,0 +1,58 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method};
+use rocket::{Request, Response};
+
+/// Fairing adding CORS headers for an allow-list of origins.
+///
+/// The Origin header of the request is reflected back only when it is in the list, other requests
+/// get no CORS headers but Vary. Safer than a blanket '*' once credentials are involved.
+/// Preflight requests are answered with the methods of the resource's Allow header and the
+/// request headers the client asked for.
+pub struct Cors {
+    /// Origins allowed to make cross-origin requests, e.g. "https://example.com"
+    pub allowed_origins: Vec<String>,
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS allow-list",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if self.allowed_origins.is_empty() {
+            return;
+        }
+        // The response differs by origin, whether allowed, disallowed or missing. Caches must not serve
+        // a response to other origins
+        res.adjoin_header(Header::new("Vary", "Origin"));
+
+        let origin = match req.headers().get_one("Origin") {
+            Some(origin) => origin,
+            None => return,
+        };
+        if !self.allowed_origins.iter().any(|allowed| allowed == origin) {
+            return;
+        }
+
+        res.set_header(Header::new(
+            "Access-Control-Allow-Origin",
+            origin.to_string(),
+        ));
+
+        if req.method() == Method::Options {
+            if let Some(allow) = res.headers().get_one("Allow").map(String::from) {
+                res.set_header(Header::new("Access-Control-Allow-Methods", allow));
+            }
+            if let Some(headers) = req.headers().get_one("Access-Control-Request-Headers") {
+                res.set_header(Header::new(
+                    "Access-Control-Allow-Headers",
+                    headers.to_string(),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use rocket::http::Status;
+use rocket::tokio::task::spawn_blocking;
+use rocket::tokio::time::timeout;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Handed to work running under the request timeout to decide whether its result may still be kept.
+///
+/// The work and the timeout race to claim the deadline and only one of them wins. Work has to claim it
+/// before committing any change, so a request that has been answered as timed out changes nothing.
+pub struct Deadline {
+    /// Whether the work or the timeout has claimed the deadline
+    claimed: Arc<AtomicBool>,
+}
+
+impl Deadline {
+    /// Claims the deadline for the work
+    ///
+    /// Returns false if the request has already timed out, in which case the work must be discarded
+    pub fn claim(&self) -> bool {
+        !self.claimed.swap(true, Ordering::SeqCst)
+    }
+}
+
+/// Runs blocking work on a separate thread and gives up on it once the timeout elapses
+///
+/// Handlers can't interrupt the work itself, so it keeps running in the background after the timeout.
+/// It has to claim the deadline before committing its result, see Deadline.
+///
+/// Returns the result of the work, 503 Service Unavailable if it timed out or 500 Internal Server Error
+/// if it panicked
+///
+/// # Arguments
+///
+/// * 'limit' - Timeout in milliseconds, 0 waits for the work however long it takes
+///
+/// * 'work' - The work, given the deadline to claim before committing
+pub async fn run<T, F>(limit: u64, work: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce(Deadline) -> T + Send + 'static,
+{
+    let claimed = Arc::new(AtomicBool::new(false));
+    let deadline = Deadline {
+        claimed: claimed.clone(),
+    };
+    let mut task = spawn_blocking(move || work(deadline));
+    if limit == 0 {
+        return task.await.map_err(|_| Status::InternalServerError);
+    }
+
+    match timeout(Duration::from_millis(limit), &mut task).await {
+        Ok(joined) => joined.map_err(|_| Status::InternalServerError),
+        Err(_) if !claimed.swap(true, Ordering::SeqCst) => Err(Status::ServiceUnavailable),
+        // The work claimed the deadline just before it elapsed and is about to finish
+        Err(_) => task.await.map_err(|_| Status::InternalServerError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[rocket::async_test]
+    async fn slow_work_times_out_and_may_not_commit() {
+        let committed = Arc::new(AtomicBool::new(false));
+        let result = run(10, {
+            let committed = committed.clone();
+            move |deadline| {
+                sleep(Duration::from_millis(100));
+                committed.store(deadline.claim(), Ordering::SeqCst);
+            }
+        })
+        .await;
+
+        assert_eq!(result, Err(Status::ServiceUnavailable));
+        // The work is left running and finds the deadline taken
+        sleep(Duration::from_millis(200));
+        assert!(!committed.load(Ordering::SeqCst));
+    }
+
+    #[rocket::async_test]
+    async fn work_finishing_in_time_is_returned() {
+        let result = run(1000, |deadline| deadline.claim()).await;
+
+        assert_eq!(result, Ok(true));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::board::{bitboards_to_board, board_to_bitboards, normalize_board};
+use crate::game::{validate_position, CreateError};
+use serde::Deserialize;
+
+/// Text formats a board can be written in
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardFormat {
+    /// The internal format, 9 characters in row order, e.g. "XO--X---O"
+    Flat,
+
+    /// 9 comma separated cells in row order, e.g. "X,O,-,-,X,-,-,-,O"
+    Csv,
+
+    /// One row of 3 cells per line, e.g. "XO-\n-X-\n--O"
+    Rows,
+
+    /// The bitboards of X and O as comma separated numbers, bit i standing for cell i, e.g. "17,258"
+    Bitboard,
+}
+
+/// Parses a board written in the format into the internal format
+///
+/// Lowercase signs and the configured empty cell aliases are normalized, and in the CSV format an
+/// empty cell stands for an empty square. Bitboards may only use the 9 lowest bits and may not both
+/// claim a cell. The parsed board must be a legal position.
+///
+/// # Arguments
+///
+/// * 'input' - The board written in the format
+///
+/// * 'format' - Format the board is written in
+///
+/// * 'empty_aliases' - Characters accepted for an empty cell in addition to '-'
+pub fn parse(input: &str, format: BoardFormat, empty_aliases: &str) -> Result<String, CreateError> {
+    let board: String = match format {
+        BoardFormat::Flat => String::from(input),
+        BoardFormat::Csv => input
+            .split(',')
+            .map(|cell| match cell.trim() {
+                "" => "-",
+                cell => cell,
+            })
+            .collect(),
+        BoardFormat::Rows => input
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect(),
+        BoardFormat::Bitboard => {
+            let invalid = || CreateError::InvalidBitboards(String::from(input));
+            let (x, o) = input.split_once(',').ok_or_else(invalid)?;
+            let x = x.trim().parse::<u16>().map_err(|_| invalid())?;
+            let o = o.trim().parse::<u16>().map_err(|_| invalid())?;
+            if (x | o) >> 9 != 0 || x & o != 0 {
+                return Err(invalid());
+            }
+            bitboards_to_board(x, o)
+        }
+    };
+
+    let board = normalize_board(&board, empty_aliases);
+    validate_position(&board)?;
+    Ok(board)
+}
+
+/// Writes a board in the internal format in the given format
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board in the internal format
+///
+/// * 'format' - Format to write the board in
+pub fn render(board: &str, format: BoardFormat) -> String {
+    let cells: Vec<String> = board.chars().map(String::from).collect();
+    match format {
+        BoardFormat::Flat => String::from(board),
+        BoardFormat::Csv => cells.join(","),
+        BoardFormat::Rows => cells
+            .chunks(3)
+            .map(|row| row.concat())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        BoardFormat::Bitboard => {
+            let (x, o) = board_to_bitboards(board);
+            format!("{},{}", x, o)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_round_trip_through_each_text_format() {
+        let board = "XO--X---O";
+        for format in [BoardFormat::Flat, BoardFormat::Csv, BoardFormat::Rows] {
+            let rendered = render(board, format);
+            assert_eq!(parse(&rendered, format, " _.").unwrap(), board);
+        }
+        assert_eq!(render(board, BoardFormat::Csv), "X,O,-,-,X,-,-,-,O");
+        assert_eq!(render(board, BoardFormat::Rows), "XO-\n-X-\n--O");
+    }
+
+    #[test]
+    fn illegal_positions_are_rejected_during_conversion() {
+        assert!(parse("X,X,X,-,-,-,-,-,-", BoardFormat::Csv, " _.").is_err());
+    }
+}
@@ -1,11 +1,26 @@
+use crate::ai::best_move;
+use crate::board::{
+    canonical, clear, custom_marks, empty_indices, has_line, internal_marks, normalize_board,
+    place, side_to_move, trim_board, winner,
+};
+use crate::config::Config;
 use crate::game::GameStatus::{OWon, XWon, DRAW};
-use rand::Rng;
-use serde::{Deserialize, Serialize};
+use crate::packed::PackedBoard;
+use crate::sign::Sign;
+use crate::strategy::{MoveStrategy, Random, Strategies, DEFAULT_STRATEGY};
+use log::warn;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 /// Used to help keep track of game status
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum GameStatus {
     RUNNING,
     XWon,
@@ -13,14 +28,435 @@ pub enum GameStatus {
     DRAW,
 }
 
+impl GameStatus {
+    /// Returns the status as it's written in the status field of a game
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameStatus::RUNNING => "RUNNING",
+            GameStatus::XWon => "X_WON",
+            GameStatus::OWon => "O_WON",
+            GameStatus::DRAW => "DRAW",
+        }
+    }
+}
+
+impl TryFrom<&str> for GameStatus {
+    type Error = ();
+
+    /// Converts a status as it's written in the status field of a game, e.g. "X_WON"
+    fn try_from(string: &str) -> Result<GameStatus, ()> {
+        match string {
+            "RUNNING" => Ok(GameStatus::RUNNING),
+            "X_WON" => Ok(GameStatus::XWon),
+            "O_WON" => Ok(GameStatus::OWon),
+            "DRAW" => Ok(GameStatus::DRAW),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Reasons a game can't be created from a submitted board
+#[derive(Debug, PartialEq)]
+pub enum CreateError {
+    /// The board doesn't have exactly 9 cells, carries the amount of characters it has
+    InvalidLength(usize),
+
+    /// The board contains a character other than X, O and -, carries the first such character
+    InvalidCharacter(char),
+
+    /// The board is well formed but the amount of X's and O's is not a legal turn count
+    IllegalTurnBalance,
+
+    /// The board is well formed but can't be reached in a game that is still running
+    IllegalPosition,
+
+    /// The board already contains a completed line
+    AlreadyWon,
+
+    /// No strategy is registered with the requested name, carries the name
+    UnknownStrategy(String),
+
+    /// A move in the notation is not a sign and cell index pair, carries the move
+    InvalidNotation(String),
+
+    /// The requested sign of the player is not one of the two marks, carries the requested sign
+    InvalidSign(String),
+
+    /// The board is not a pair of bitboards of distinct cells, carries the submitted board
+    InvalidBitboards(String),
+
+    /// The probability of the computer taking X is not between 0 and 1, carries the probability
+    InvalidBias(f64),
+}
+
+impl CreateError {
+    /// Describes why the board was rejected, without saying what was being done with it
+    pub fn reason(&self) -> String {
+        match self {
+            CreateError::InvalidLength(length) => {
+                format!("board must be exactly 9 characters, got {}", length)
+            }
+            CreateError::InvalidCharacter(character) => {
+                format!("board contains invalid character '{}'", character)
+            }
+            CreateError::IllegalTurnBalance => String::from("illegal turn balance"),
+            CreateError::IllegalPosition => {
+                String::from("position is not reachable in a running game")
+            }
+            CreateError::AlreadyWon => String::from("board already contains a win"),
+            CreateError::UnknownStrategy(name) => format!("unknown strategy '{}'", name),
+            CreateError::InvalidSign(sign) => format!("sign '{}' is neither X nor O", sign),
+            CreateError::InvalidBitboards(board) => format!(
+                "'{}' is not two bitboards of distinct cells such as 17,258",
+                board
+            ),
+            CreateError::InvalidBias(bias) => {
+                format!("computer_x_bias {} is not between 0 and 1", bias)
+            }
+            CreateError::InvalidNotation(token) => {
+                format!("move '{}' is not a sign and cell index such as X:4", token)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CreateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unable to create game: {}", self.reason())
+    }
+}
+
+/// Payload of a request to create a new game
+///
+/// Carries the starting board along with options that only apply when the game is created.
+#[derive(Deserialize, Default)]
+pub struct NewGame {
+    /// The starting board
+    board: String,
+
+    /// Whether the game may be played to completion by the computer through the autoplay endpoint
+    #[serde(default)]
+    autoplay: bool,
+
+    /// Whether the board is a mid-game position to resume rather than a starting board
+    #[serde(default)]
+    resume: bool,
+
+    /// Whether a resumed board may already be won or full, creating a game that is already over
+    #[serde(default)]
+    allow_finished: bool,
+
+    /// Whether the signs of both sides are placed by the player without the computer ever moving
+    #[serde(default)]
+    analysis: bool,
+
+    /// Seed for the computer's random moves, generated from entropy when omitted
+    #[serde(default)]
+    seed: Option<u64>,
+
+    /// Name of the strategy the computer chooses its moves with, random when omitted
+    #[serde(default)]
+    strategy: Option<String>,
+
+    /// Sign the player plays on an empty board, assigned randomly when omitted
+    #[serde(default)]
+    sign: Option<String>,
+
+    /// Probability of the computer taking X when the sign is assigned randomly, a fair coin when omitted
+    #[serde(default)]
+    computer_x_bias: Option<f64>,
+
+    /// Whether two players play each other by link, each side moving with its own move token.
+    /// Played like an analysis game, the computer never moves
+    #[serde(default)]
+    pvp: bool,
+}
+
+impl NewGame {
+    /// Returns whether the game is played between two players with a move token for each side
+    pub fn is_pvp(&self) -> bool {
+        self.pvp
+    }
+
+    /// Creates a request for a game starting from the board without any other options
+    ///
+    /// # Arguments
+    ///
+    /// * 'board' - The starting board
+    pub fn with_board(board: String) -> NewGame {
+        NewGame {
+            board,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a request for a game starting from an empty board, as started for each game of a match
+    ///
+    /// # Arguments
+    ///
+    /// * 'strategy' - Name of the strategy the computer chooses its moves with, random when None
+    ///
+    /// * 'sign' - Sign the player plays, assigned randomly when None
+    pub fn empty(strategy: Option<String>, sign: Option<String>) -> NewGame {
+        NewGame {
+            board: String::from("---------"),
+            strategy,
+            sign,
+            ..Default::default()
+        }
+    }
+}
+
+/// Reasons a move can be rejected
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    /// The game has already ended
+    GameOver,
+
+    /// The submitted board contains characters other than X, O and -
+    InvalidBoard,
+
+    /// The submitted board doesn't add exactly one sign of the player
+    InvalidMove,
+
+    /// The submitted board changes a sign that was already placed
+    OverwrittenCell,
+
+    /// The computer has yet to reply to the player's previous move
+    OutOfTurn,
+
+    /// The submitted board changes more than one cell, e.g. two moves of the player
+    MultipleCellsChanged,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "game is over"),
+            MoveError::InvalidBoard => write!(f, "board contains invalid characters"),
+            MoveError::InvalidMove => {
+                write!(f, "board does not add exactly one sign of the player")
+            }
+            MoveError::OverwrittenCell => write!(f, "move overwrites a placed sign"),
+            MoveError::OutOfTurn => write!(f, "computer has yet to reply to the previous move"),
+            MoveError::MultipleCellsChanged => write!(f, "board changes more than one cell"),
+        }
+    }
+}
+
+/// Result of a move accepted by make_move
+pub struct MoveOutcome {
+    /// Whether the move or the computer's reply ended the game
+    pub game_over: bool,
+
+    /// The computer's reply, None if the computer didn't reply
+    pub computer_move: Option<Move>,
+}
+
+/// A sign placed on the board, recorded in the history of the game
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Move {
+    /// Index of the cell the sign was placed in
+    pub index: usize,
+
+    /// The placed sign
+    pub sign: Sign,
+
+    /// Side that placed the sign, "PLAYER" or "COMPUTER"
+    pub by: String,
+}
+
+impl Move {
+    /// Creates a history entry
+    ///
+    /// # Arguments
+    ///
+    /// * 'index' - Index of the cell the sign was placed in
+    ///
+    /// * 'sign' - The placed sign
+    ///
+    /// * 'by' - Side that placed the sign, "PLAYER" or "COMPUTER"
+    pub fn new(index: usize, sign: Sign, by: &str) -> Move {
+        Move {
+            index,
+            sign,
+            by: String::from(by),
+        }
+    }
+}
+
 /// Container for a HashMap of Player X/O choices for each game by ID
 ///
 /// This is stored separately to the game object itself as the game object has to be able to be returned
 /// in a specific way with specific fields as outlined in the document.
 ///
 /// The HashMap is wrapped in a Mutex to allow it to be handled asynchronously by all functions that need it.
+/// The Mutex is shared through an Arc so background tasks can hold on to the same map as the handlers.
+#[derive(Clone)]
 pub struct PlayerList {
-    pub player_map: Mutex<HashMap<String, char>>,
+    pub player_map: Arc<Mutex<HashMap<String, Sign>>>,
+}
+
+/// Container for HashMaps of the read-only, owner and move tokens for each game by ID
+///
+/// Read tokens allow a game to be shared publicly through the view endpoint without handing out
+/// anything that can be used to make moves. Owner tokens identify the client that created a game and
+/// can be shared by many games of the same client. Move tokens are only issued for PvP games, one for
+/// the player of X and one for the player of O, so neither can move for the other.
+/// They are kept out of the game object so that listing games doesn't leak them.
+pub struct TokenList {
+    pub read_tokens: Mutex<HashMap<String, String>>,
+    pub owner_tokens: Mutex<HashMap<String, String>>,
+    pub move_tokens: Mutex<HashMap<String, (String, String)>>,
+}
+
+impl TokenList {
+    /// Generates and stores a new read token for the game
+    ///
+    /// Returns the token
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game the token grants access to
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn issue_read_token(&self, game_id: &str) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        self.read_tokens
+            .lock()
+            .unwrap()
+            .insert(String::from(game_id), token.clone());
+        token
+    }
+
+    /// Generates and stores the move tokens of both sides of a PvP game
+    ///
+    /// Returns the tokens of X and O
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game the tokens allow moves in
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn issue_move_tokens(&self, game_id: &str) -> (String, String) {
+        let tokens = (
+            Uuid::new_v4().simple().to_string(),
+            Uuid::new_v4().simple().to_string(),
+        );
+        self.move_tokens
+            .lock()
+            .unwrap()
+            .insert(String::from(game_id), tokens.clone());
+        tokens
+    }
+
+    /// Returns the move tokens of X and O for the game, None if it's not a PvP game
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn move_tokens(&self, game_id: &str) -> Option<(String, String)> {
+        self.move_tokens.lock().unwrap().get(game_id).cloned()
+    }
+
+    /// Records the owner of the game, generating a new owner token if the client didn't supply one
+    ///
+    /// Returns the owner token
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// * 'token' - Owner token supplied by the client, if any
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn set_owner(&self, game_id: &str, token: Option<String>) -> String {
+        let token = token.unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+        self.owner_tokens
+            .lock()
+            .unwrap()
+            .insert(String::from(game_id), token.clone());
+        token
+    }
+
+    /// Returns the IDs of all games owned by the token
+    ///
+    /// # Arguments
+    ///
+    /// * 'token' - Owner token
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn owned_games(&self, token: &str) -> Vec<String> {
+        self.owner_tokens
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == token)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Removes every token of the game, used when the game is deleted
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the deleted game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn forget(&self, game_id: &str) {
+        self.read_tokens.lock().unwrap().remove(game_id);
+        self.owner_tokens.lock().unwrap().remove(game_id);
+        self.move_tokens.lock().unwrap().remove(game_id);
+    }
+
+    /// Removes the tokens of games that no longer exist
+    ///
+    /// Returns the amount of token entries removed, counting each read, owner and move token entry
+    ///
+    /// # Arguments
+    ///
+    /// * 'exists' - Whether the game with the ID exists
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn retain_existing(&self, exists: impl Fn(&str) -> bool) -> usize {
+        let mut read_tokens = self.read_tokens.lock().unwrap();
+        let mut owner_tokens = self.owner_tokens.lock().unwrap();
+        let mut move_tokens = self.move_tokens.lock().unwrap();
+
+        let before = read_tokens.len() + owner_tokens.len() + move_tokens.len();
+        read_tokens.retain(|id, _| exists(id));
+        owner_tokens.retain(|id, _| exists(id));
+        move_tokens.retain(|id, _| exists(id));
+        before - (read_tokens.len() + owner_tokens.len() + move_tokens.len())
+    }
+
+    /// Returns whether the token is the read token of the game
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// * 'token' - Token supplied by the client
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn can_read(&self, game_id: &str, token: &str) -> bool {
+        self.read_tokens
+            .lock()
+            .unwrap()
+            .get(game_id)
+            .map(|t| t.as_str())
+            == Some(token)
+    }
 }
 
 /// Container for a HashMap of games by ID.
@@ -28,449 +464,2047 @@ pub struct PlayerList {
 /// This is used as the active storage for the program. Scalable in reasonable amounts considering the
 /// performance of rust but a database would be preferable for a large scale deployment.
 /// Database would be added complexity in anything but the largest deployments.
+/// The Mutex is shared through an Arc so background tasks can hold on to the same map as the handlers.
+#[derive(Clone)]
 pub struct GameList {
-    pub list: Mutex<HashMap<String, Game>>,
+    pub list: Arc<Mutex<HashMap<String, Game>>>,
+}
+
+impl GameList {
+    /// Ends running games in which the player has not moved within the timeout
+    ///
+    /// The player is always the side to move in a running game, so an idle game is forfeited to the
+    /// computer, or declared a draw if configured so. Forfeited games are kept in the list.
+    ///
+    /// Returns the IDs of the forfeited games
+    ///
+    /// # Arguments
+    ///
+    /// * 'timeout' - Seconds the player may take to move
+    ///
+    /// * 'draw' - Whether idle games end in a draw instead of a computer win
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn forfeit_idle_games(&self, timeout: u64, draw: bool) -> Vec<String> {
+        let mut list = self.list.lock().unwrap();
+        let now = unix_time();
+
+        let mut forfeited = vec![];
+        for (id, game) in list.iter_mut() {
+            // Games waiting for the computer after an undo aren't the player's fault
+            if game.status.as_deref() != Some("RUNNING")
+                || game.archived
+                || game.analysis
+                || game.history.last().map(|last| last.by.as_str()) == Some("PLAYER")
+                || now.saturating_sub(game.updated_at) < timeout
+            {
+                continue;
+            }
+            let status = match game.player_sign() {
+                _ if draw => DRAW,
+                Sign::X => OWon,
+                Sign::O => XWon,
+            };
+            game.set_status(status);
+            game.set_game_over("TIMEOUT");
+            game.touch();
+            forfeited.push(id.clone());
+        }
+        forfeited
+    }
+}
+
+/// Cumulative thinking time of both sides of a game in milliseconds
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TimeUsed {
+    /// Time used by the side playing X
+    pub x: u64,
+
+    /// Time used by the side playing O
+    pub o: u64,
 }
 
 /// Struct that represents the game object that stores all the information about the game and
 /// handles all the logic within its functions. Derives traits to allow it to be converted to json
 /// and cloned
+///
+/// The derived (de)serialization is generated as inherent functions with `remote = "Self"` so that
+/// the Serialize implementation below can extend the stored fields with computed ones.
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Game {
     /// The game's UUID, read-only. Generated on object creation.
     id: Option<String>,
 
-    /// The board state
-    board: String,
+    /// The board state, packed as it's kept for the lifetime of the game
+    board: PackedBoard,
 
     /// The game status
     status: Option<String>,
+
+    /// Whether the game has ended. Set by make_move so clients can react to the end of the game
+    /// without inspecting the status string.
+    /// Never read from payloads, games loaded from an export have it recomputed by restore_outcome.
+    #[serde(default, skip_deserializing)]
+    game_over: bool,
+
+    /// Which side made the move that ended the game, "PLAYER" or "COMPUTER".
+    /// "TIMEOUT" if the player forfeited by not moving in time.
+    /// None while the game is running.
+    /// Never read from payloads, games loaded from an export have it recomputed by restore_outcome.
+    #[serde(default, skip_deserializing)]
+    ended_by: Option<String>,
+
+    /// The 1-based number of the current move, derived from the amount of signs on the board.
+    /// Frozen at the number of the final move once the game is over.
+    #[serde(default)]
+    turn: u32,
+
+    /// Whether the game may be played to completion by the computer through the autoplay endpoint.
+    /// Can only be set when the game is created.
+    #[serde(default)]
+    autoplay: bool,
+
+    /// Whether the player places the signs of both sides and the computer never moves.
+    /// Can only be set when the game is created.
+    #[serde(default)]
+    analysis: bool,
+
+    /// Seed the computer's random moves are derived from, so the game can be replayed.
+    /// Either supplied when the game is created or generated from entropy.
+    #[serde(default)]
+    seed: u64,
+
+    /// Name of the strategy the computer chooses its moves with.
+    /// Can only be set when the game is created.
+    #[serde(default = "default_strategy")]
+    strategy: String,
+
+    /// Cumulative thinking time of each side, measured between the moves.
+    #[serde(default)]
+    time_used: TimeUsed,
+
+    /// Unix time in milliseconds when the side to move started thinking. Internal to the clock.
+    #[serde(default, skip_serializing)]
+    clock_started: u64,
+
+    /// Version of the game state, incremented every time moves are made.
+    /// Lets clients tell whether the game has changed since they last saw it.
+    #[serde(default)]
+    version: u32,
+
+    /// Unix time in seconds when the game was created.
+    #[serde(default)]
+    created_at: u64,
+
+    /// Unix time in seconds of the last change to the game state.
+    /// Used to forfeit games in which the player has stopped moving.
+    #[serde(default)]
+    updated_at: u64,
+
+    /// Whether the game has been soft deleted. Archived games are kept but hidden from listings.
+    #[serde(default)]
+    archived: bool,
+
+    /// Signs placed since the game was created or its board was set, oldest first.
+    /// The starting board is the current board with these cells cleared.
+    #[serde(default)]
+    history: Vec<Move>,
+
+    /// Amount of clients streaming the game's events. Not tracked by the game itself,
+    /// filled in when the game is returned or streamed, so it's never read from clients.
+    #[serde(skip_deserializing)]
+    spectators: u32,
+
+    /// How the computer chose its reply to the player's move: "random", "win", "block" or "minimax".
+    /// Only reported in the response to the move, stored games don't keep it.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    computer_move_kind: Option<String>,
+
+    /// Whether the computer opened the game, see is_new.
+    /// Only reported in the response to the creation request, stored games don't keep it.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    is_new: Option<bool>,
+
+    /// Sign the player places, kept with the game so moves don't need to look it up in the PlayerList.
+    /// Internal, the PlayerList remains the record of the player's sign for the API.
+    #[serde(skip)]
+    player_sign: Option<Sign>,
+
+    /// Amount of times each position has occurred, keyed by its canonical form so symmetric positions
+    /// count as one. Positions recur when moves are taken back and made again. Internal, rebuilt from
+    /// the history when a game is loaded.
+    #[serde(skip)]
+    positions: HashMap<String, u32>,
+}
+
+impl Serialize for Game {
+    /// Serializes the stored fields along with the computed ones
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Stored fields of the game followed by fields derived from them
+        #[derive(Serialize)]
+        struct GameResponse<'a> {
+            #[serde(flatten, serialize_with = "serialize_fields")]
+            game: &'a Game,
+
+            /// The board as 3 rows of 3 cells, derived from the flat board
+            grid: Vec<Vec<char>>,
+        }
+
+        /// Serializes the stored fields with the derived implementation
+        fn serialize_fields<S: Serializer>(game: &&Game, serializer: S) -> Result<S::Ok, S::Error> {
+            Game::serialize(game, serializer)
+        }
+
+        GameResponse {
+            game: self,
+            grid: self.get_grid(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Game {
+    /// Deserializes the stored fields with the derived implementation
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Game, D::Error> {
+        Game::deserialize(deserializer)
+    }
 }
 
 impl Game {
     /// Creates a new game instance
     /// Checks whether the board is an acceptable starting board and returns and error if not.
+    /// Malformed boards and boards with an illegal amount of signs are reported as distinct errors.
     ///
     /// The function validates the initial board state and fails if the board is not a valid starting board.
+    /// Lowercase signs and the configured empty cell aliases are normalized before validation, and
+    /// surrounding whitespace is trimmed unless strict boards are configured.
     ///
     /// If the player has made a starting move, the function checks which sign the user has used and
     /// saves it to PlayerList.
     /// If the player has not made a move, the function will randomly assign itself (and the player)
     /// a sign, and makes a first move.
+    /// If the player has requested a sign, the player is assigned that sign instead and the computer
+    /// only makes the first move if the player chose O, as X always moves first.
+    /// All random choices derive from the seed of the request, or a generated seed if none is given.
+    /// The computer's replies are chosen with the requested strategy, which must be registered.
+    ///
+    /// If the request resumes a mid-game position, any position reachable in a running game is accepted.
+    /// The player is assigned the sign whose turn it is and no computer move is made.
+    /// Analysis games accept boards the same way, as the computer never moves in them.
     ///
     /// Returns the new game object
     ///
     /// # Arguments
     ///
-    /// * 'board' - Starting board
+    /// * 'new_game' - Creation request containing the starting board and options
     ///
     /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
     ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    ///
     /// # Panics
     /// May panic if the the function is unable to open up the mutex
-    pub fn new(mut board: String, player_list: &PlayerList) -> Result<Game, &'static str> {
+    pub fn new(
+        new_game: &NewGame,
+        player_list: &PlayerList,
+        config: &Config,
+        strategies: &Strategies,
+    ) -> Result<Game, CreateError> {
+        let strategy_name = new_game.strategy.clone().unwrap_or_else(default_strategy);
+        let strategy = strategies
+            .get(&strategy_name)
+            .ok_or_else(|| CreateError::UnknownStrategy(strategy_name.clone()))?;
+        let requested_sign = new_game
+            .sign
+            .as_ref()
+            .map(|sign| {
+                // Read the same way as the signs on a submitted board
+                let internal = normalize_board(&internal_marks(sign.trim(), config.marks), "");
+                Sign::try_from(internal.as_str())
+                    .map_err(|_| CreateError::InvalidSign(sign.clone()))
+            })
+            .transpose()?;
+        let computer_x_bias = new_game.computer_x_bias.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&computer_x_bias) {
+            return Err(CreateError::InvalidBias(computer_x_bias));
+        }
+        let mut board = prepare_board(&new_game.board, config);
         let player_move;
-        let mut lock = player_list.player_map.lock().unwrap(); // Bringing player map
+        let mut history = vec![];
         let uuid = Some(Uuid::new_v4().to_string()); // Generating UUID
         let uuid_copy = uuid.clone().unwrap(); // copy for map use, Safely unwrappable
+        let seed = new_game.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
 
-        // Validating board size
-        if board.len() != 9 {
-            return Err("Unable to create game: invalid board!");
-        }
-        // Correct characters and count
-        let mut x_count = 0;
-        let mut o_count = 0;
-        for character in board.chars() {
-            match character {
-                'X' => {
-                    x_count += 1;
-                    continue;
-                }
-                'O' => {
-                    o_count += 1;
-                    continue;
-                }
-                '-' => continue,
-                _ => return Err("Unable to create game: invalid board!"),
+        let (x_count, o_count) = count_signs(&board)?;
+        let resume = new_game.resume || new_game.analysis || new_game.pvp;
+        if resume {
+            validate_turn_balance(x_count, o_count)?;
+            validate_winners(&board, x_count, o_count)?;
+            // Finished positions can't be resumed unless explicitly allowed
+            match evaluate_board(&board) {
+                _ if new_game.allow_finished => {}
+                GameStatus::RUNNING => {}
+                GameStatus::XWon | GameStatus::OWon => return Err(CreateError::AlreadyWon),
+                GameStatus::DRAW => return Err(CreateError::IllegalPosition),
             }
-        }
         // Checking if there's a valid number characters to start game
-        if ((x_count > 1) || (o_count > 1)) || (x_count == 1 && o_count == 1) {
-            return Err("Unable to create game: invalid starting board");
+        } else if ((x_count > 1) || (o_count > 1)) || (x_count == 1 && o_count == 1) {
+            return Err(CreateError::IllegalTurnBalance);
         }
 
+        if resume {
+            // Player takes over the side whose turn it is, X moves first on an even board
+            player_move = side_to_move(&board, Sign::X);
         // If board started empty, make first move
         // Implementing a best move algorithm was out of scope for this so a random slot will be used
-        if (x_count == 0) && (o_count == 0) {
-            let mut rng = rand::thread_rng();
+        } else if (x_count == 0) && (o_count == 0) {
             let random = rng.gen_range(0..9); // Random number
-            let sign_select = rng.gen_range(0..100);
-            let first_move;
 
-            // place random sign on random spot
-            if (sign_select % 2) == 0 {
-                first_move = "O";
-                player_move = 'X';
-            } else {
-                first_move = "X";
-                player_move = 'O';
-            }
+            player_move = match requested_sign {
+                Some(sign) => sign,
+                // place random sign on random spot, the computer takes X with the requested bias
+                None if rng.gen_bool(computer_x_bias) => Sign::O,
+                None => Sign::X,
+            };
             // Making the first move by replacing a random tile with with the random sign.
-            board.replace_range(random..random + 1, first_move);
-        } else if (x_count == 1) && (o_count == 0) {
-            player_move = 'X'; // If player has placed an X to start
-
-            // Computer response move
-            board = make_computer_move(board, "O");
+            // A player who chose X moves first on the empty board instead.
+            if requested_sign != Some(Sign::X) {
+                board = place(&board, random, player_move.opponent());
+                history.push(Move::new(random, player_move.opponent(), "COMPUTER"));
+            }
         } else {
-            player_move = 'O'; // if board is not empty and not X then player placed O
+            if (x_count == 1) && (o_count == 0) {
+                player_move = Sign::X; // If player has placed an X to start
+            } else {
+                player_move = Sign::O; // if board is not empty and not X then player placed O
+            }
+            let first = board.find(player_move.as_char()).unwrap(); // Board has exactly one sign
+            history.push(Move::new(first, player_move, "PLAYER"));
 
             // Computer response move
-            board = make_computer_move(board, "X");
+            let (reply, index, _) =
+                make_computer_move(board, player_move.opponent(), strategy, &mut rng);
+            board = reply;
+            history.push(Move::new(index, player_move.opponent(), "COMPUTER"));
         }
 
         // Creating game object to be returned
-        let game = Game {
-            id: uuid,
-            status: Some(String::from("RUNNING")),
+        let mut game = Game::start(
+            uuid,
             board,
-        };
+            history,
+            player_move,
+            new_game,
+            seed,
+            strategy_name,
+        );
+        if game.check_win_conditions() {
+            game.end_from_board(player_move);
+        }
 
-        // Adding player and game id to map
-        let _ = lock.insert(uuid_copy, player_move);
+        // Adding player and game id to map, only locking it once the game is ready so concurrent
+        // creations don't wait on each other's validation and computer moves
+        let _ = player_list
+            .player_map
+            .lock()
+            .unwrap()
+            .insert(uuid_copy, player_move);
 
         Ok(game)
     }
 
-    /// Sets the board game board
-    /// Does NOT validate the board
+    /// Creates a game from a transcript in the notation of to_notation, replaying the moves from an
+    /// empty board to build the position and history.
     ///
-    /// # Arguments
-    /// * 'board' - A representation of the board
-    pub fn set_board(&mut self, board: String) {
-        self.board = board
-    }
-
-    /// Gets the current board
+    /// Either sign may open, after which the signs must alternate. The notation doesn't record who made
+    /// the moves, so the player is assumed to be the side to move after the last move, as when resuming
+    /// a game, and the other side's moves are recorded as the computer's. Finished games are accepted
+    /// and created as over. No computer move is made.
     ///
-    /// Returns a string representing the current board.
-    pub fn get_board(&self) -> &String {
-        &self.board
-    }
-
-    /// Gets the current status of the game
-    pub fn _get_status(&self) -> &Option<String> {
-        &self.status
-    }
-
-    /// Sets the status of the game to one of 4 options defined by GameStatus
+    /// Returns the reason the notation was rejected as an error
     ///
     /// # Arguments
     ///
-    /// 'game_status' - GameStatus used to set the game status
-    fn set_status(&mut self, game_status: GameStatus) {
-        match game_status {
-            GameStatus::RUNNING => self.status = Some(String::from("RUNNING")),
-            GameStatus::XWon => self.status = Some(String::from("X_WON")),
-            GameStatus::OWon => self.status = Some(String::from("O_WON")),
-            GameStatus::DRAW => self.status = Some(String::from("DRAW")),
-        }
-    }
-
-    /// Returns the id of the game
+    /// * 'notation' - Moves as sign and cell index pairs separated by whitespace, e.g. "X:4 O:0"
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn import(
+        notation: &str,
+        player_list: &PlayerList,
+        config: &Config,
+    ) -> Result<Game, CreateError> {
+        let moves = parse_notation(&internal_marks(notation, config.marks))?;
+
+        let mut board = String::from("---------");
+        for (i, (sign, index)) in moves.iter().enumerate() {
+            if i > 0 && moves[i - 1].0 == *sign {
+                return Err(CreateError::IllegalTurnBalance);
+            }
+            if winner(&board).is_some() {
+                return Err(CreateError::AlreadyWon);
+            }
+            if board.as_bytes()[*index] != b'-' {
+                return Err(CreateError::IllegalPosition);
+            }
+            board = place(&board, *index, *sign);
+        }
+
+        let player_sign = moves
+            .last()
+            .map(|(sign, _)| sign.opponent())
+            .unwrap_or(Sign::X);
+        let history = moves
+            .iter()
+            .map(|(sign, index)| {
+                let by = if *sign == player_sign {
+                    "PLAYER"
+                } else {
+                    "COMPUTER"
+                };
+                Move::new(*index, *sign, by)
+            })
+            .collect();
+
+        let uuid = Uuid::new_v4().to_string();
+        let options = NewGame::default();
+        let seed = rand::thread_rng().gen();
+        let mut game = Game::start(
+            Some(uuid.clone()),
+            board,
+            history,
+            player_sign,
+            &options,
+            seed,
+            default_strategy(),
+        );
+        if game.check_win_conditions() {
+            game.end_from_board(player_sign);
+        }
+        player_list
+            .player_map
+            .lock()
+            .unwrap()
+            .insert(uuid, player_sign);
+
+        Ok(game)
+    }
+
+    /// Assembles a running game from an already validated position
+    ///
+    /// # Arguments
+    ///
+    /// * 'id' - ID of the game
+    ///
+    /// * 'board' - Representation of the board
+    ///
+    /// * 'history' - Moves leading to the board
+    ///
+    /// * 'player_sign' - Sign the player places
+    ///
+    /// * 'new_game' - Creation request the options of the game are taken from
+    ///
+    /// * 'seed' - Seed the computer's random moves are derived from
+    ///
+    /// * 'strategy' - Name of the strategy the computer chooses its moves with
+    fn start(
+        id: Option<String>,
+        board: String,
+        history: Vec<Move>,
+        player_sign: Sign,
+        new_game: &NewGame,
+        seed: u64,
+        strategy: String,
+    ) -> Game {
+        let mut game = Game {
+            id,
+            status: None,
+            board: PackedBoard::pack(&board),
+            game_over: false,
+            ended_by: None,
+            turn: 0,
+            autoplay: new_game.autoplay,
+            analysis: new_game.analysis || new_game.pvp,
+            seed,
+            strategy,
+            time_used: TimeUsed::default(),
+            clock_started: unix_time_ms(),
+            version: 0,
+            created_at: unix_time(),
+            updated_at: unix_time(),
+            archived: false,
+            history,
+            spectators: 0,
+            computer_move_kind: None,
+            is_new: None,
+            player_sign: Some(player_sign),
+            positions: HashMap::new(),
+        };
+        game.set_status(GameStatus::RUNNING);
+        game.rebuild_positions();
+        game
+    }
+
+    /// Copies the game into a new independent game with a fresh ID
+    ///
+    /// The board, status and options are copied and the player keeps the same sign in the copy.
+    /// The version of the copy starts over.
+    ///
+    /// # Arguments
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn duplicate(&self, player_list: &PlayerList) -> Game {
+        let uuid = Uuid::new_v4().to_string();
+        player_list
+            .player_map
+            .lock()
+            .unwrap()
+            .insert(uuid.clone(), self.player_sign());
+
+        Game {
+            id: Some(uuid),
+            version: 0,
+            created_at: unix_time(),
+            updated_at: unix_time(),
+            ..self.clone()
+        }
+    }
+
+    /// Moves the game to another ID, taking the player's sign choice along
+    ///
+    /// # Arguments
+    ///
+    /// * 'id' - The new ID of the game
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn set_id(&mut self, id: String, player_list: &PlayerList) {
+        let mut lock = player_list.player_map.lock().unwrap();
+        if let Some(player_sign) = lock.remove(&self.id.clone().unwrap_or_default()) {
+            lock.insert(id.clone(), player_sign);
+        }
+        self.id = Some(id);
+    }
+
+    /// Gets the sign the player places
+    pub fn player_sign(&self) -> Sign {
+        self.player_sign.unwrap() // Every game is given the player's sign when created or imported
+    }
+
+    /// Sets the sign the player places, for games restored without it
+    /// Does NOT update the PlayerList
+    ///
+    /// # Arguments
+    ///
+    /// * 'player_sign' - Sign the player places
+    pub fn set_player_sign(&mut self, player_sign: Sign) {
+        self.player_sign = Some(player_sign)
+    }
+
+    /// Sets the board game board and records the position as having occurred
+    /// Does NOT validate the board
+    ///
+    /// # Arguments
+    /// * 'board' - A representation of the board
+    pub fn set_board(&mut self, board: String) {
+        count_position(&mut self.positions, &board);
+        self.board = PackedBoard::pack(&board)
+    }
+
+    /// Returns how many times the current position or a position symmetric to it has occurred in the game,
+    /// including now
+    pub fn repetitions(&self) -> u32 {
+        let count = self.positions.get(&canonical(&self.get_board()).0).copied();
+        count.unwrap_or(0).max(1)
+    }
+
+    /// Checks that a game loaded from an export is consistent, so later moves can't fail on it
+    ///
+    /// The board must be a reachable position and the history must replay onto it: every move places
+    /// its sign in a distinct cell that holds that sign, the signs alternate and no move follows a win.
+    /// Outside analysis games the moves of the player's sign must be the player's. The status must be
+    /// a known status, match a finished board and agree with whether the game is over.
+    ///
+    /// Returns the reason the game is inconsistent as an error
+    ///
+    /// # Arguments
+    ///
+    /// * 'player_sign' - Sign the player places according to the export
+    pub fn validate_restored(&self, player_sign: Sign) -> Result<(), String> {
+        validate_position(&self.get_board()).map_err(|e| e.reason())?;
+
+        let mut seen = [false; 9];
+        for past in &self.history {
+            if past.index >= 9 || seen[past.index] {
+                return Err(format!(
+                    "history places a sign in cell {} twice or off the board",
+                    past.index
+                ));
+            }
+            seen[past.index] = true;
+            if self.get_board().as_bytes()[past.index] != past.sign.as_char() as u8 {
+                return Err(format!(
+                    "history doesn't match the board in cell {}",
+                    past.index
+                ));
+            }
+        }
+        let mut board = self
+            .history
+            .iter()
+            .fold(self.get_board(), |board, past| clear(&board, past.index));
+        for (i, past) in self.history.iter().enumerate() {
+            if i > 0 && self.history[i - 1].sign == past.sign {
+                return Err(String::from("history has a sign moving twice in a row"));
+            }
+            if winner(&board).is_some() {
+                return Err(String::from("history has a move after the game was won"));
+            }
+            let next = place(&board, past.index, past.sign);
+            validate_move(&board, &next, past.sign).map_err(|e| e.to_string())?;
+            let by_player = past.by == "PLAYER";
+            if !self.analysis && by_player != (past.sign == player_sign) {
+                return Err(format!(
+                    "history move in cell {} is by the wrong side",
+                    past.index
+                ));
+            }
+            board = next;
+        }
+
+        let status = self
+            .status
+            .as_deref()
+            .and_then(|status| GameStatus::try_from(status).ok())
+            .ok_or_else(|| String::from("status is not a known status"))?;
+        let evaluated = evaluate_board(&self.get_board());
+        if evaluated != GameStatus::RUNNING && status != evaluated {
+            return Err(String::from("status doesn't match the board"));
+        }
+        Ok(())
+    }
+
+    /// Recomputes whether a game loaded from an export is over and which side ended it
+    ///
+    /// The side that made the last move ended a game finished on the board, the side owning the line
+    /// if the board was already finished when the game was created. A game that is over on a running
+    /// board was forfeited on time.
+    ///
+    /// # Arguments
+    ///
+    /// 'player_sign' - Sign of the player in the game
+    pub fn restore_outcome(&mut self, player_sign: Sign) {
+        self.game_over = false;
+        self.ended_by = None;
+        if self.status.as_deref() == Some("RUNNING") {
+            return;
+        }
+
+        match (evaluate_board(&self.get_board()), self.history.last()) {
+            (GameStatus::RUNNING, _) => self.set_game_over("TIMEOUT"),
+            (_, Some(last)) => {
+                let ended_by = last.by.clone();
+                self.set_game_over(&ended_by);
+            }
+            (_, None) => self.end_from_board(player_sign),
+        }
+    }
+
+    /// Counts the positions along the history from the starting board to the current board, for games
+    /// assembled from a position or loaded from an export
+    ///
+    /// Positions that occurred in moves taken back are not in the history and are not counted.
+    pub fn rebuild_positions(&mut self) {
+        self.positions.clear();
+        let mut board = self
+            .history
+            .iter()
+            .fold(self.get_board(), |board, past| clear(&board, past.index));
+        count_position(&mut self.positions, &board);
+        for past in &self.history {
+            board = place(&board, past.index, past.sign);
+            count_position(&mut self.positions, &board);
+        }
+    }
+
+    /// Gets the current board
+    ///
+    /// Returns a string representing the current board.
+    pub fn get_board(&self) -> String {
+        self.board.unpack()
+    }
+
+    /// Gets the current status of the game
+    pub fn get_status(&self) -> &Option<String> {
+        &self.status
+    }
+
+    /// Sets the status of the game to one of 4 options defined by GameStatus
+    /// and updates the turn number to match the board
+    ///
+    /// # Arguments
+    ///
+    /// 'game_status' - GameStatus used to set the game status
+    fn set_status(&mut self, game_status: GameStatus) {
+        self.status = Some(String::from(game_status.as_str()));
+
+        // The next move while running, the move that ended the game once finished
+        let marks = self.get_board().chars().filter(|char| *char != '-').count() as u32;
+        self.turn = match game_status {
+            GameStatus::RUNNING => marks + 1,
+            _ => marks,
+        };
+    }
+
+    /// Marks the game as over and records which side ended it
+    ///
+    /// # Arguments
+    ///
+    /// 'ended_by' - Side that made the final move, "PLAYER" or "COMPUTER", or "TIMEOUT" for a forfeit
+    fn set_game_over(&mut self, ended_by: &str) {
+        self.game_over = true;
+        self.ended_by = Some(String::from(ended_by));
+    }
+
+    /// Marks a game that was set to a finished board as over
+    ///
+    /// The side owning the winning line is recorded as having ended the game, a draw records no side.
+    /// In analysis games the player owns both sides.
+    ///
+    /// # Arguments
+    ///
+    /// 'player_sign' - Sign of the player in the game
+    fn end_from_board(&mut self, player_sign: Sign) {
+        self.game_over = true;
+        self.ended_by = match winner(&self.get_board()) {
+            Some(sign) if sign == player_sign || self.analysis => Some(String::from("PLAYER")),
+            Some(_) => Some(String::from("COMPUTER")),
+            None => None,
+        };
+    }
+
+    /// Returns the board as 3 rows of 3 cells
+    pub fn get_grid(&self) -> Vec<Vec<char>> {
+        self.get_board()
+            .chars()
+            .collect::<Vec<char>>()
+            .chunks(3)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    /// Renders the board as a 3x3 grid of plain text
+    ///
+    /// Cells in a row are separated by '|' and rows by a line of dashes, e.g.
+    /// ```text
+    /// X | O | -
+    /// ---------
+    /// ```
+    pub fn to_ascii(&self) -> String {
+        let rows: Vec<String> = self
+            .get_grid()
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            })
+            .collect();
+
+        let mut ascii = rows.join("\n---------\n");
+        ascii.push('\n');
+        ascii
+    }
+
+    /// Writes the history of the game as a compact transcript of sign and cell index pairs, e.g.
+    /// ```text
+    /// X:4 O:0 X:8
+    /// ```
+    ///
+    /// Returns an empty string if no moves have been made.
+    pub fn to_notation(&self) -> String {
+        self.history
+            .iter()
+            .map(|past| format!("{}:{}", past.sign.as_str(), past.index))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Reconstructs the board as it was after the first ply moves of the history
+    ///
+    /// Ply 0 is the starting position, which isn't empty for resumed games or games whose board was set.
+    /// Returns None if fewer than ply moves have been made.
+    ///
+    /// # Arguments
+    ///
+    /// * 'ply' - Amount of moves made
+    pub fn board_at(&self, ply: usize) -> Option<String> {
+        let later_moves = self.history.get(ply..)?;
+        Some(
+            later_moves
+                .iter()
+                .fold(self.get_board(), |board, past| clear(&board, past.index)),
+        )
+    }
+
+    /// Renders the board as a 300x300 SVG image with grid lines and X/O glyphs
+    ///
+    /// The image is built directly as a string to avoid pulling in a graphics library.
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::from(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"300\" height=\"300\" viewBox=\"0 0 300 300\">",
+        );
+        svg.push_str("<rect width=\"300\" height=\"300\" fill=\"white\"/>");
+
+        // Grid lines
+        for offset in [100, 200] {
+            svg.push_str(&format!(
+                "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"300\" stroke=\"black\" stroke-width=\"4\"/>",
+                offset
+            ));
+            svg.push_str(&format!(
+                "<line x1=\"0\" y1=\"{0}\" x2=\"300\" y2=\"{0}\" stroke=\"black\" stroke-width=\"4\"/>",
+                offset
+            ));
+        }
+
+        // Glyphs, each cell is 100x100 with a 20 unit margin
+        for (i, char) in self.get_board().chars().enumerate() {
+            let x = (i % 3) * 100;
+            let y = (i / 3) * 100;
+            match char {
+                'X' => {
+                    svg.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"crimson\" stroke-width=\"8\"/>",
+                        x + 20,
+                        y + 20,
+                        x + 80,
+                        y + 80
+                    ));
+                    svg.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"crimson\" stroke-width=\"8\"/>",
+                        x + 80,
+                        y + 20,
+                        x + 20,
+                        y + 80
+                    ));
+                }
+                'O' => {
+                    svg.push_str(&format!(
+                        "<circle cx=\"{}\" cy=\"{}\" r=\"30\" stroke=\"navy\" stroke-width=\"8\" fill=\"none\"/>",
+                        x + 50,
+                        y + 50
+                    ));
+                }
+                _ => continue,
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Returns the generator for the computer's next random choices
+    ///
+    /// Derived from the seed and the version of the game, so replaying the same requests on a game
+    /// with the same seed produces the same computer moves.
+    fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed.wrapping_add(u64::from(self.version)))
+    }
+
+    /// Looks up the strategy the computer chooses its moves with
+    ///
+    /// Falls back to the default strategy if the game's strategy is no longer registered.
+    ///
+    /// # Arguments
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    fn strategy<'a>(&self, strategies: &'a Strategies) -> &'a dyn MoveStrategy {
+        strategies
+            .get(&self.strategy)
+            .unwrap_or_else(|| strategies.get(DEFAULT_STRATEGY).unwrap()) // Always registered
+    }
+
+    /// Returns the version of the game state
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the Unix time in seconds when the game was created
+    pub fn get_created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the Unix time in seconds of the last change to the game state
+    pub fn get_updated_at(&self) -> u64 {
+        self.updated_at
+    }
+
+    /// Returns the amount of signs on the board
+    pub fn move_count(&self) -> usize {
+        self.get_board().chars().filter(|cell| *cell != '-').count()
+    }
+
+    /// Returns whether the game has ended
+    pub fn is_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Returns the sign of the side to move, None once the game is over
+    ///
+    /// The player is to move unless the computer has yet to reply, in analysis games X and O take turns
+    /// starting with X.
+    ///
+    /// # Arguments
+    ///
+    /// * 'player_sign' - Sign of the player
+    pub fn to_move(&self, player_sign: Sign) -> Option<Sign> {
+        if self.game_over {
+            return None;
+        }
+        if self.analysis {
+            return Some(side_to_move(&self.get_board(), Sign::X));
+        }
+        match self.history.last() {
+            Some(last) if last.by == "PLAYER" => Some(player_sign.opponent()),
+            _ => Some(player_sign),
+        }
+    }
+
+    /// Returns the side that ended the game, None while it's running
+    pub fn get_ended_by(&self) -> &Option<String> {
+        &self.ended_by
+    }
+
+    /// Returns the signs placed since the game was created or its board was set, oldest first
+    pub fn get_history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Sets the amount of clients streaming the game
+    ///
+    /// # Arguments
+    ///
+    /// 'spectators' - Amount of connected event streams
+    pub fn set_spectators(&mut self, spectators: u32) {
+        self.spectators = spectators
+    }
+
+    /// Forgets how the computer chose its last reply once the response to the move has been built
+    pub fn clear_computer_move_kind(&mut self) {
+        self.computer_move_kind = None
+    }
+
+    /// Reports whether the computer opened the game in its is_new field, done for the response to the
+    /// creation request only
+    pub fn report_is_new(&mut self) {
+        self.is_new = Some(self.is_new())
+    }
+
+    /// Returns whether the game has been soft deleted
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+
+    /// Soft deletes the game, keeping it retrievable but hidden from listings
+    pub fn archive(&mut self) {
+        if !self.archived {
+            self.archived = true;
+            self.touch();
+        }
+    }
+
+    /// Records a change to the game state by bumping the version and the time of the last change
+    ///
+    /// Restarts the clock, the side to move after the change starts thinking now.
+    fn touch(&mut self) {
+        self.version += 1;
+        self.updated_at = unix_time();
+        self.clock_started = unix_time_ms();
+    }
+
+    /// Restarts the clock of a game loaded from an export, the side to move starts thinking now
+    ///
+    /// The clock isn't exported, without restarting it the time since the epoch would be charged.
+    pub fn restart_clock(&mut self) {
+        self.clock_started = unix_time_ms();
+    }
+
+    /// Adds the time elapsed since the clock was started to the side and restarts the clock
+    ///
+    /// Returns the total time used by the side in milliseconds
+    ///
+    /// # Arguments
+    ///
+    /// * 'sign' - Sign of the side that was thinking
+    fn charge_clock(&mut self, sign: Sign) -> u64 {
+        let now = unix_time_ms();
+        let elapsed = now.saturating_sub(self.clock_started);
+        self.clock_started = now;
+        let used = match sign {
+            Sign::X => &mut self.time_used.x,
+            Sign::O => &mut self.time_used.o,
+        };
+        *used += elapsed;
+        *used
+    }
+
+    /// Returns whether the computer opened the game, which happens when it's created with an empty board
+    ///
+    /// False for games the player opened and for resumed positions.
+    pub fn is_new(&self) -> bool {
+        self.history
+            .first()
+            .is_some_and(|first| first.by == "COMPUTER")
+    }
+
+    /// Returns the approximate amount of memory used by the game in bytes, including its heap allocations
+    pub fn approximate_size(&self) -> usize {
+        let strings = [&self.id, &self.status, &self.ended_by]
+            .iter()
+            .map(|field| field.as_ref().map_or(0, String::capacity))
+            .sum::<usize>();
+        let moves = self.history.capacity() * std::mem::size_of::<Move>()
+            + self
+                .history
+                .iter()
+                .map(|past| past.by.capacity())
+                .sum::<usize>();
+
+        std::mem::size_of::<Game>() + self.board.heap_size() + strings + moves
+    }
+
+    /// Returns the ETag of the game state, the quoted version
+    pub fn get_etag(&self) -> String {
+        format!("\"{}\"", self.version)
+    }
+
+    /// Returns the id of the game
     pub fn get_id(&self) -> &Option<String> {
         &self.id
     }
 
-    /// Checks the board to determine if any win conditions are met.
-    /// If win conditions are met, the status of the game will be updated.
+    /// Checks the board to determine if any win conditions are met.
+    /// If win conditions are met, the status of the game will be updated.
+    ///
+    /// The board is evaluated with evaluate_board, which checks the lines on bitboards.
+    ///
+    /// Returns True if any win conditions are met
+    /// Returns False if no win conditions are met
+    /// DRAW counts as a win condition
+    pub fn check_win_conditions(&mut self) -> bool {
+        let status = evaluate_board(&self.get_board());
+        self.set_status(status);
+        status != GameStatus::RUNNING
+    }
+
+    /// Accepts move by player, and makes a move in response.
+    /// Computer will make their own move randomly as implementing best move algorithm was out of scope
+    /// for this.
+    ///
+    /// In analysis games the move must place the sign of the side to move, X and O strictly alternating
+    /// starting with X, and the computer never replies.
+    ///
+    /// The time since the previous move is added to the mover's time_used. If that exceeds the time
+    /// budget the move is not placed and the game is forfeited by the mover instead.
+    ///
+    /// Lowercase signs and the configured empty cell aliases in the new board are normalized before validation,
+    /// and surrounding whitespace is trimmed unless strict boards are configured.
+    ///
+    /// Rejected moves are logged at warn level with the reason and the submitted and stored boards
+    /// to help diagnose misbehaving clients.
+    ///
+    /// Updates the game in place and returns the computer's reply and whether the game ended, or the reason
+    /// the move was rejected as an error
+    /// # Arguments
+    ///
+    /// * 'new_board' - A representation of the updated board with a yet to be validated move.
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    pub fn make_move(
+        &mut self,
+        new_board: String,
+        config: &Config,
+        strategies: &Strategies,
+    ) -> Result<MoveOutcome, MoveError> {
+        let new_board = prepare_board(&new_board, config);
+        let stored_board = self.get_board();
+        self.computer_move_kind = None; // Set again if the computer replies
+
+        let result = self.apply_move(
+            new_board.clone(),
+            config.time_budget,
+            self.strategy(strategies),
+        );
+        if let Err(e) = &result {
+            warn!(
+                "Rejected move: game_id={} reason=\"{}\" submitted={:?} stored={:?}",
+                self.id.as_deref().unwrap_or_default(),
+                e,
+                new_board,
+                stored_board
+            );
+        }
+        result.map(|computer_move| MoveOutcome {
+            game_over: self.is_over(),
+            computer_move,
+        })
+    }
+
+    /// Returns whether the computer would reply to the move if it was made with make_move, without making it
+    ///
+    /// False for analysis and PvP games, games that are over, moves make_move would reject and moves that
+    /// end the game themselves. Running out of time isn't considered, the move is assumed to be in time.
+    ///
+    /// # Arguments
+    ///
+    /// * 'new_board' - A representation of the updated board with a yet to be validated move.
+    ///
+    /// * 'config' - Runtime configuration of the API
+    pub fn replies_to(&self, new_board: &str, config: &Config) -> bool {
+        if self.analysis || self.status.as_deref() != Some("RUNNING") {
+            return false;
+        }
+        if self.history.last().map(|last| last.by.as_str()) == Some("PLAYER") {
+            return false;
+        }
+        let new_board = prepare_board(new_board, config);
+        if validate_move(&self.get_board(), &new_board, self.player_sign()).is_err() {
+            return false;
+        }
+        winner(&new_board).is_none() && new_board.contains('-')
+    }
+
+    /// Validates the player's move and makes the computer's counter move, see make_move
+    ///
+    /// Returns the computer's reply, None if the computer didn't reply
+    ///
+    /// # Arguments
+    ///
+    /// * 'new_board' - A normalized representation of the updated board with a yet to be validated move.
+    ///
+    /// * 'time_budget' - Total thinking time in milliseconds each side may use, 0 for no limit
+    ///
+    /// * 'strategy' - Strategy the computer chooses its reply with
+    fn apply_move(
+        &mut self,
+        new_board: String,
+        time_budget: u64,
+        strategy: &dyn MoveStrategy,
+    ) -> Result<Option<Move>, MoveError> {
+        let game_status = self.status.clone().unwrap();
+        let mut player_move = self.player_sign();
+        let mut current_board = self.get_board();
+        if self.analysis {
+            // Both sides are played by the player, taking turns
+            player_move = side_to_move(&current_board, Sign::X);
+        }
+        let computer_sign = player_move.opponent();
+
+        if game_status != *"RUNNING" {
+            // Game is over, don't accept a move
+            return Err(MoveError::GameOver);
+        }
+        // The computer's reply was undone and has not been made again
+        if !self.analysis && self.history.last().map(|last| last.by.as_str()) == Some("PLAYER") {
+            return Err(MoveError::OutOfTurn);
+        }
+
+        // The new board must be the current board with one sign of the mover added
+        let index = validate_move(&current_board, &new_board, player_move)?;
+
+        // Move arrived after the mover ran out of time, the game is lost on time instead
+        let used = self.charge_clock(player_move);
+        if time_budget > 0 && used > time_budget {
+            self.set_status(match player_move {
+                Sign::X => OWon,
+                Sign::O => XWon,
+            });
+            self.set_game_over("TIMEOUT");
+            self.touch();
+            return Ok(None);
+        }
+
+        // If move is valid, set the updated board to be the current board
+        self.history.push(Move::new(index, player_move, "PLAYER"));
+        self.set_board(new_board);
+        self.touch();
+
+        // update current board variable
+        current_board = self.get_board();
+
+        // Checking if player move has fulfilled win conditions, if not make counter move.
+        // Filling the last cell without a line is a DRAW ended by the player, so the computer is
+        // never asked to reply on a full board.
+        if self.check_win_conditions() {
+            self.set_game_over("PLAYER");
+            return Ok(None);
+        }
+        if self.analysis {
+            return Ok(None);
+        }
+
+        // Making counter computer move
+        let (current_board, index, kind) =
+            make_computer_move(current_board, computer_sign, strategy, &mut self.rng());
+
+        // Updating board with computer move
+        self.set_board(current_board);
+        self.computer_move_kind = Some(String::from(kind));
+        let computer_move = Move::new(index, computer_sign, "COMPUTER");
+        self.history.push(computer_move.clone());
+        self.charge_clock(computer_sign);
+
+        // Checking win conditions after computer move
+        if self.check_win_conditions() {
+            self.set_game_over("COMPUTER");
+        }
+
+        Ok(Some(computer_move))
+    }
+
+    /// Plays a sequence of moves by the player, with the computer replying after each as in make_move.
     ///
-    /// The function iterates through the board checking for each win condition.
-    /// Multiple methods of determining win conditions are used for both proof of concept and convenience.
+    /// Stops at the first illegal move or once the game is over, the moves before it stay played.
     ///
-    /// Returns True if any win conditions are met
-    /// Returns False if no win conditions are met
-    /// DRAW counts as a win condition
-    pub fn check_win_conditions(&mut self) -> bool {
-        let board_rows: Vec<&str>;
-        let current_board = &self.board.clone();
-        let row0 = &current_board[0..3];
-        let row1 = &current_board[3..6];
-        let row2 = &current_board[6..];
-        board_rows = vec![row0, row1, row2];
-
-        // temporary variables for logic use
-        let mut win_x: bool;
-        let mut win_o: bool;
-
-        // This is a bit slow but there's no clever way to take the character as an input
-        // since the game object stores it as a single string anyway and the function would
-        // just have to be duplicated on each type of move function.
-        // That and since the board is 9 characters long, the impact is negligible even on low power devices
-        // Despite appearing rather convoluted, should only be O(5n)
-
-        // Checking rows for X
-        for row in &board_rows {
-            win_x = true;
-            for char in row.chars() {
-                // If all chars are X, win is true and loop won't break
-                if char != 'X' {
-                    win_x = false;
-                    break;
-                }
+    /// Returns the amount of moves played, along with the reason the next move was rejected if the
+    /// sequence stopped at an illegal move
+    ///
+    /// # Arguments
+    ///
+    /// * 'indices' - Indexes of the cells to place the player's sign in, in order
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    pub fn play_moves(
+        &mut self,
+        indices: &[usize],
+        config: &Config,
+        strategies: &Strategies,
+    ) -> (usize, Option<MoveError>) {
+        let player_sign = self.player_sign();
+
+        let mut applied = 0;
+        for &index in indices {
+            if self.game_over {
+                break;
             }
-            // terminates with a win, X has won, break loop
-            if win_x {
-                let _ = &self.set_status(XWon);
-                return true;
+            if index >= self.get_board().len() {
+                return (applied, Some(MoveError::InvalidMove));
             }
-        }
-
-        // Checking rows for O
-        for row in &board_rows {
-            win_o = true;
-            for char in row.chars() {
-                // If all chars are O, win is true and loop won't break
-                if char != 'O' {
-                    win_o = false;
-                    break;
-                }
+            if self.get_board().as_bytes()[index] != b'-' {
+                return (applied, Some(MoveError::OverwrittenCell));
             }
-            // terminates with a win, O has won, break loop
-            if win_o {
-                let _ = &self.set_status(OWon);
-                return true;
+            // Both sides are played by the player in analysis games
+            let sign = if self.analysis {
+                side_to_move(&self.get_board(), Sign::X)
+            } else {
+                player_sign
+            };
+            // Submitted the same way as a client would, in the configured marks
+            let new_board = custom_marks(&place(&self.get_board(), index, sign), config.marks);
+            if let Err(e) = self.make_move(new_board, config, strategies) {
+                return (applied, Some(e));
             }
+            applied += 1;
         }
+        (applied, None)
+    }
 
-        //Checking columns
-        // Preparing rows for parallel iteration
-        let r0 = row0.chars();
-        let r12 = row1.chars().zip(row2.chars());
-
-        // Iterating over all the rows parallel
-        for (r0, r12) in r0.zip(r12) {
-            let r0_char = r0;
-            let (r1_char, r2_char) = r12;
-
-            // If all characters are the same, check which one they are and behave accordingly
-            if (r0_char == r1_char) && (r2_char == r0_char) {
-                match r0_char {
-                    'X' => {
-                        self.set_status(XWon);
-                        return true;
-                    }
-                    'O' => {
-                        self.set_status(OWon);
-                        return true;
-                    }
-                    _ => continue,
-                }
-            }
+    /// Plays the game to completion with the computer making the moves of both sides.
+    ///
+    /// The computer's own sign is played optimally using minimax while the player's sign is played
+    /// with random moves, which is useful for verifying the engine and for demos.
+    /// The amount of moves is capped to the amount of empty cells so the loop always terminates.
+    ///
+    /// Returns an error if the game is not marked for autoplay, is an analysis game or is not running.
+    pub fn autoplay(&mut self) -> Result<(), &'static str> {
+        if !self.autoplay {
+            return Err("Unable to autoplay: game is not marked for autoplay");
+        }
+        if self.analysis {
+            return Err("Unable to autoplay: the computer doesn't move in analysis games");
+        }
+        if self.status.as_deref() != Some("RUNNING") {
+            return Err("Unable to autoplay: game is over");
         }
 
-        // Checking diagonals
-        // Grabbing the characters we need to check
-        // initializing with a default value and mutable because of rust security, overwritten by loop
-        let mut zero = '-';
-        let mut two = '-';
-        let mut four = '-';
-        let mut six = '-';
-        let mut eight = '-';
-        // Assigning the signs we want to a variable.
-        for (i, char) in current_board.chars().enumerate() {
-            match i {
-                0 => zero = char,
-                2 => two = char,
-                4 => four = char,
-                6 => six = char,
-                8 => eight = char,
-                _ => continue,
+        let player_sign = self.player_sign();
+        let computer_sign = player_sign.opponent();
+
+        // The computer is to move after its reply was undone without being made again, signs alternate from there
+        let mut sign = match self.to_move(player_sign) {
+            Some(sign) => sign,
+            None => return Err("Unable to autoplay: game is over"),
+        };
+        let mut rng = self.rng();
+        for _ in 0..empty_indices(&self.get_board()).len() {
+            let ended_by = if sign == computer_sign {
+                let index = match best_move(&self.get_board(), sign) {
+                    Some(index) => index,
+                    None => break,
+                };
+                self.set_board(place(&self.get_board(), index, sign));
+                self.history.push(Move::new(index, sign, "COMPUTER"));
+                "COMPUTER"
+            } else {
+                let (board, index, _) =
+                    make_computer_move(self.get_board(), sign, &Random, &mut rng);
+                self.set_board(board);
+                self.history.push(Move::new(index, sign, "PLAYER"));
+                "PLAYER"
+            };
+
+            if self.check_win_conditions() {
+                self.set_game_over(ended_by);
+                break;
             }
+            sign = sign.opponent();
         }
-        // Comparisons
-        // 0 - 4 - 8 Diagonal
-        if (zero == eight) && (zero == four) {
-            match zero {
-                'X' => {
-                    self.set_status(XWon);
-                    return true;
-                }
-                'O' => {
-                    self.set_status(OWon);
-                    return true;
-                }
-                _ => {}
+        self.touch();
+
+        Ok(())
+    }
+
+    /// Takes back the computer's last reply, leaving the player's move before it on the board.
+    ///
+    /// The game is running again afterwards, even if the reply had ended it. With reply the computer
+    /// immediately replies again, which may pick a different cell. Without it the game waits for the
+    /// computer and moves by the player are rejected until it replies.
+    ///
+    /// Returns an error if the last move in the history was not made by the computer.
+    ///
+    /// # Arguments
+    ///
+    /// * 'reply' - Whether the computer makes a new reply after the undo
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    pub fn undo_computer_move(
+        &mut self,
+        reply: bool,
+        strategies: &Strategies,
+    ) -> Result<(), &'static str> {
+        let last = match self.history.last() {
+            Some(last) if last.by == "COMPUTER" => last.clone(),
+            _ => return Err("Unable to undo: last move was not made by the computer"),
+        };
+        self.history.pop();
+        self.set_board(clear(&self.get_board(), last.index));
+        self.game_over = false;
+        self.ended_by = None;
+        self.set_status(GameStatus::RUNNING);
+        // Touched before the reply so it's drawn from a new seed, not the one the undone move was drawn from
+        self.touch();
+
+        if reply {
+            let (board, index, _) = make_computer_move(
+                self.get_board(),
+                last.sign,
+                self.strategy(strategies),
+                &mut self.rng(),
+            );
+            self.set_board(board);
+            self.history.push(Move::new(index, last.sign, "COMPUTER"));
+            if self.check_win_conditions() {
+                self.set_game_over("COMPUTER");
             }
         }
-        // 2 - 4 - 8 Diagonal
-        if (two == four) && (two == six) {
-            match two {
-                'X' => {
-                    self.set_status(XWon);
-                    return true;
-                }
-                'O' => {
-                    self.set_status(OWon);
-                    return true;
-                }
-                _ => {}
+
+        Ok(())
+    }
+
+    /// Makes a single move for the side to move with the game's strategy, without a move by the player.
+    ///
+    /// Outside analysis games the computer only moves on its own turn, which is after its reply was undone
+    /// without being made again. In analysis games it moves for whichever side is to move.
+    ///
+    /// Returns the computer's move, or an error if the game is over or it's the player's turn.
+    ///
+    /// # Arguments
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    pub fn step_computer(&mut self, strategies: &Strategies) -> Result<Move, &'static str> {
+        let sign = match self.to_move(self.player_sign()) {
+            None => return Err("Unable to move: game is over"),
+            Some(sign) if !self.analysis && sign == self.player_sign() => {
+                return Err("Unable to move: it's the player's turn")
             }
+            Some(sign) => sign,
+        };
+
+        let (board, index, kind) = make_computer_move(
+            self.get_board(),
+            sign,
+            self.strategy(strategies),
+            &mut self.rng(),
+        );
+        self.set_board(board);
+        self.computer_move_kind = Some(String::from(kind));
+        let computer_move = Move::new(index, sign, "COMPUTER");
+        self.history.push(computer_move.clone());
+        self.charge_clock(sign);
+        if self.check_win_conditions() {
+            self.set_game_over("COMPUTER");
         }
+        self.touch();
 
-        // Finally, if no win conditions are met and the function returned, checking for a draw
-        // If no slots are unfilled (-), and previous conditions did not return true, game is draw
-        for char in current_board.chars() {
-            if char == '-' {
-                // no win conditions met, unfilled slot, game still live
-                self.set_status(GameStatus::RUNNING);
-                return false;
-            }
+        Ok(computer_move)
+    }
+
+    /// Lets the player steal the computer's opening move under the pie rule.
+    ///
+    /// The player and computer swap signs, so the opening sign becomes the player's, and the computer
+    /// immediately replies with its new sign. Swapping leaves the computer to move with the sign it just
+    /// took, replying right away keeps the player to move after the swap as before it.
+    ///
+    /// Returns an error if the game is over or anything but the computer's single opening move has been
+    /// played, a sign on a resumed board was never the computer's move.
+    ///
+    /// # Arguments
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn swap_sides(
+        &mut self,
+        player_list: &PlayerList,
+        strategies: &Strategies,
+    ) -> Result<(), &'static str> {
+        if self.analysis {
+            return Err("Unable to swap: the computer doesn't move in analysis games");
         }
-        // Game has no empty slots and no win conditions have been met
-        self.set_status(DRAW);
-        true
+        let computer_sign = self.player_sign().opponent();
+        if self.to_move(self.player_sign()) != Some(self.player_sign()) {
+            return Err("Unable to swap: game is over or it's the computer's turn");
+        }
+
+        let signs = self.get_board().chars().filter(|cell| *cell != '-').count();
+        let opening = matches!(self.history.as_slice(), [only] if only.by == "COMPUTER");
+        if signs != 1 || !opening {
+            return Err("Unable to swap: only the computer's opening move may have been played");
+        }
+
+        // The opening move is now the player's
+        self.player_sign = Some(computer_sign);
+        player_list
+            .player_map
+            .lock()
+            .unwrap()
+            .insert(self.id.clone().unwrap(), computer_sign); // Game exists, safe to unwrap
+        for past in self.history.iter_mut() {
+            past.by = String::from("PLAYER");
+        }
+
+        let (board, index, _) = make_computer_move(
+            self.get_board(),
+            computer_sign.opponent(),
+            self.strategy(strategies),
+            &mut self.rng(),
+        );
+        self.set_board(board);
+        self.history
+            .push(Move::new(index, computer_sign.opponent(), "COMPUTER"));
+        self.touch();
+
+        Ok(())
     }
 
-    /// Accepts move by player, and makes a move in response.
-    /// Computer will make their own move randomly as implementing best move algorithm was out of scope
-    /// for this.
+    /// Sets the status regardless of the board, for driving clients through end states in tests.
+    ///
+    /// The game is over for any status but RUNNING. No side is recorded as having ended it.
     ///
     /// # Arguments
     ///
-    /// * 'new_board' - A representation of the updated board with a yet to be validated move.
+    /// * 'game_status' - The new status
+    pub fn force_status(&mut self, game_status: GameStatus) {
+        self.set_status(game_status);
+        self.game_over = game_status != GameStatus::RUNNING;
+        self.ended_by = None;
+        self.touch();
+    }
+
+    /// Replaces the board with any legal position and recomputes the status without a computer move.
     ///
-    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
-    pub fn make_move(&mut self, new_board: String, player_list: &PlayerList) -> bool {
-        let game_status = self.status.clone().unwrap();
-        let lock = player_list.player_map.lock().unwrap(); // Bringing player map
-        let game_id = &self.id.clone().unwrap();
-        let player_move = lock.get(game_id).unwrap(); // Function can't be called without the game existing, safe to unwrap
-        let mut current_board = self.get_board().clone();
-        let computer_sign;
+    /// Meant for setting up test positions. Finished positions are accepted and end the game,
+    /// a running position must have the player to move unless the player plays both sides.
+    /// Positions that can't be reached in a game, such as both sides having a line, are rejected.
+    ///
+    /// Returns the reason the board was rejected as an error
+    ///
+    /// # Arguments
+    ///
+    /// * 'board' - Representation of the new board
+    ///
+    /// * 'config' - Runtime configuration of the API
+    pub fn set_position(&mut self, board: &str, config: &Config) -> Result<(), CreateError> {
+        let board = prepare_board(board, config);
+        let (x_count, o_count) = count_signs(&board)?;
+        validate_turn_balance(x_count, o_count)?;
+        validate_winners(&board, x_count, o_count)?;
 
-        if game_status != *"RUNNING" {
-            // Game is over, don't accept a move
-            return false;
+        let player_sign = self.player_sign();
+        let player_count = if player_sign == Sign::X {
+            x_count
+        } else {
+            o_count
+        };
+        let computer_count = x_count + o_count - player_count;
+        let running = winner(&board).is_none() && board.contains('-');
+        if running && !self.analysis && player_count > computer_count {
+            return Err(CreateError::IllegalPosition);
         }
 
-        // Counting current characters
-        let mut current_x = 0;
-        let mut current_o = 0;
-        let mut current_empty = 0;
+        self.set_board(board);
+        self.history.clear(); // The new board is the starting point of the history
+        self.rebuild_positions();
+        self.game_over = false;
+        self.ended_by = None;
+        if self.check_win_conditions() {
+            self.end_from_board(player_sign);
+        }
+        self.touch();
 
-        for char in current_board.chars() {
-            match char {
-                'X' => current_x += 1,
-                'O' => current_o += 1,
-                '-' => current_empty += 1,
-                _ => panic!("Current board is not valid"), // Current board should never be invalid at this stage
-            }
+        Ok(())
+    }
+}
+
+/// Validates that the new board is the current board with exactly one sign of the mover added
+///
+/// Returns the index of the cell the sign was added to, or the reason the move is illegal as an error
+///
+/// # Arguments
+///
+/// * 'current_board' - Normalized representation of the board before the move
+///
+/// * 'new_board' - Normalized representation of the board with a yet to be validated move
+///
+/// * 'sign' - Sign of the side making the move
+pub fn validate_move(current_board: &str, new_board: &str, sign: Sign) -> Result<usize, MoveError> {
+    // Counting current characters
+    let mut current_x = 0;
+    let mut current_o = 0;
+    let mut current_empty = 0;
+
+    for char in current_board.chars() {
+        match char {
+            'X' => current_x += 1,
+            'O' => current_o += 1,
+            '-' => current_empty += 1,
+            _ => return Err(MoveError::InvalidBoard), // Only stored or validated boards are compared against
+        }
+    }
+    // Counting new board signs
+    let mut new_x = 0;
+    let mut new_o = 0;
+    let mut new_empty = 0;
+
+    for char in new_board.chars() {
+        match char {
+            'X' => new_x += 1,
+            'O' => new_o += 1,
+            '-' => new_empty += 1,
+            _ => return Err(MoveError::InvalidBoard), // New move contains an invalid board, move not accepted
         }
-        // Counting new board signs
-        let mut new_x = 0;
-        let mut new_o = 0;
-        let mut new_empty = 0;
+    }
 
-        for char in new_board.chars() {
-            match char {
-                'X' => new_x += 1,
-                'O' => new_o += 1,
-                '-' => new_empty += 1,
-                _ => return false, // New move contains an invalid board, move not accepted
+    // Comparing boards to make sure no previously set moves have been altered or overridden
+    for (old, new) in current_board.chars().zip(new_board.chars()) {
+        if old == 'X' || old == 'O' {
+            if old == new {
+                continue;
             }
+            return Err(MoveError::OverwrittenCell);
         }
+    }
 
-        // Comparing boards to check validity of the move and setting computer sign
-        match player_move {
-            'X' => {
-                computer_sign = "O";
-                // Checking if the amount of X's and O's is as expected in the new board
-                if !(((new_x - current_x) == 1)
-                    && (((new_o - current_o) == 0) && ((current_empty - new_empty) == 1)))
-                {
-                    // If conditions above are not true, the move is not valid and rejected.
-                    return false;
-                }
-            }
-            'O' => {
-                computer_sign = "X";
-                // Checking if the amount of X's and O's is as expected in the new board
-                if !(((new_o - current_o) == 1)
-                    && (((new_x - current_x) == 0) && ((current_empty - new_empty) == 1)))
-                {
-                    // Same as above but with other player sign
-                    return false;
-                }
+    // A move fills exactly one cell, more mean the player moved again instead of the computer
+    let changed_cells = current_board
+        .chars()
+        .zip(new_board.chars())
+        .filter(|(old, new)| old != new)
+        .count();
+    if changed_cells > 1 {
+        return Err(MoveError::MultipleCellsChanged);
+    }
+
+    // Comparing boards to check validity of the move
+    match sign {
+        Sign::X => {
+            // Checking if the amount of X's and O's is as expected in the new board
+            if !(((new_x - current_x) == 1)
+                && (((new_o - current_o) == 0) && ((current_empty - new_empty) == 1)))
+            {
+                // If conditions above are not true, the move is not valid and rejected.
+                return Err(MoveError::InvalidMove);
             }
-            _ => panic!("Player move not set"), // Should be impossible, appropriate to panic
         }
-
-        // Comparing boards to make sure no previously set moves have been altered or overridden
-        for (old, new) in current_board.chars().zip(new_board.chars()) {
-            if old == 'X' || old == 'O' {
-                 if old == new {
-                    continue
-                }
-                return false;
+        Sign::O => {
+            // Checking if the amount of X's and O's is as expected in the new board
+            if !(((new_o - current_o) == 1)
+                && (((new_x - current_x) == 0) && ((current_empty - new_empty) == 1)))
+            {
+                // Same as above but with other player sign
+                return Err(MoveError::InvalidMove);
             }
         }
+    }
 
-        // If move is valid, set the updated board to be the current board
-        self.set_board(new_board);
+    Ok(current_board
+        .chars()
+        .zip(new_board.chars())
+        .position(|(old, new)| old != new)
+        .unwrap()) // Move adds exactly one sign
+}
 
-        // update current board variable
-        current_board = self.get_board().clone();
+/// Evaluates the state of a board without modifying any game
+///
+/// Returns the winner as XWon or OWon, DRAW for a full board without a winner, RUNNING otherwise
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+pub fn evaluate_board(board: &str) -> GameStatus {
+    match winner(board) {
+        Some(Sign::X) => XWon,
+        Some(Sign::O) => OWon,
+        None if !board.contains('-') => DRAW,
+        None => GameStatus::RUNNING,
+    }
+}
 
-        // Checking if player move has fulfilled win conditions, if not make counter move.
-        if !self.check_win_conditions() {
-            // Making counter computer move
-            let current_board = make_computer_move(current_board, computer_sign);
+/// Finds the cells where placing the sign would win the game
+///
+/// Returns the indexes of the winning cells, empty if the sign has no threats or the game is already won
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+///
+/// * 'sign' - Sign to find the threats of
+pub fn winning_cells(board: &str, sign: Sign) -> Vec<usize> {
+    if evaluate_board(board) != GameStatus::RUNNING {
+        return vec![];
+    }
+    let win = match sign {
+        Sign::X => XWon,
+        Sign::O => OWon,
+    };
+    empty_indices(board)
+        .into_iter()
+        .filter(|index| evaluate_board(&place(board, *index, sign)) == win)
+        .collect()
+}
+
+/// Finds the cells where placing the sign would create a fork, two threats at once
+///
+/// Returns the indexes of the forking cells, empty if the sign has no forks or the game is already won
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+///
+/// * 'sign' - Sign to find the forks of
+pub fn fork_cells(board: &str, sign: Sign) -> Vec<usize> {
+    if evaluate_board(board) != GameStatus::RUNNING {
+        return vec![];
+    }
+    empty_indices(board)
+        .into_iter()
+        .filter(|index| winning_cells(&place(board, *index, sign), sign).len() >= 2)
+        .collect()
+}
+
+/// Trims and normalizes a submitted board before it is validated
+///
+/// Surrounding whitespace and quotes are only trimmed when strict boards are not configured.
+/// The configured marks are replaced with the X and O used internally.
+///
+/// # Arguments
+///
+/// * 'board' - Representation of the board as submitted
+///
+/// * 'config' - Runtime configuration of the API
+pub fn prepare_board(board: &str, config: &Config) -> String {
+    let board = if config.strict_boards {
+        board
+    } else {
+        trim_board(board)
+    };
+    normalize_board(&internal_marks(board, config.marks), &config.empty_aliases)
+}
+
+/// Returns the current Unix time in seconds
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
 
-            // Updating board with computer move
-            self.set_board(current_board);
+/// Returns the current Unix time in milliseconds
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Validates the board size and characters and counts the signs on it
+///
+/// Returns the amount of X's and O's on the board
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+fn count_signs(board: &str) -> Result<(i32, i32), CreateError> {
+    // Validating board size
+    let length = board.chars().count();
+    if length != 9 {
+        return Err(CreateError::InvalidLength(length));
+    }
+    // Correct characters and count
+    let mut x_count: i32 = 0;
+    let mut o_count: i32 = 0;
+    for character in board.chars() {
+        match character {
+            'X' => x_count += 1,
+            'O' => o_count += 1,
+            '-' => continue,
+            _ => return Err(CreateError::InvalidCharacter(character)),
         }
+    }
+    Ok((x_count, o_count))
+}
 
-        // Checking win conditions after computer move
-        self.check_win_conditions();
+/// Validates that the board is well formed and its sign counts are possible in a game
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+pub fn validate_position(board: &str) -> Result<(), CreateError> {
+    let (x_count, o_count) = count_signs(board)?;
+    validate_turn_balance(x_count, o_count)?;
+    validate_winners(board, x_count, o_count)
+}
+
+/// Validates that the completed lines on the board can be reached in a game
+///
+/// The game ends with the move completing a line, so only one side can have a line and that side made
+/// the last move, which it can't have done while having fewer signs than the other side.
+///
+/// # Arguments
+///
+/// * 'board' - Normalized representation of the board
+///
+/// * 'x_count' - Amount of X's on the board
+///
+/// * 'o_count' - Amount of O's on the board
+fn validate_winners(board: &str, x_count: i32, o_count: i32) -> Result<(), CreateError> {
+    match (has_line(board, Sign::X), has_line(board, Sign::O)) {
+        (true, true) => Err(CreateError::IllegalPosition),
+        (true, false) if x_count < o_count => Err(CreateError::IllegalPosition),
+        (false, true) if o_count < x_count => Err(CreateError::IllegalPosition),
+        _ => Ok(()),
+    }
+}
 
-        true
+/// Validates that the sign counts are possible in a mid-game position
+///
+/// # Arguments
+///
+/// * 'x_count' - Amount of X's on the board
+///
+/// * 'o_count' - Amount of O's on the board
+fn validate_turn_balance(x_count: i32, o_count: i32) -> Result<(), CreateError> {
+    // Signs alternate so neither side can be more than one move ahead
+    if (x_count - o_count).abs() > 1 {
+        return Err(CreateError::IllegalTurnBalance);
     }
+    Ok(())
+}
+
+/// Parses a transcript of sign and cell index pairs separated by whitespace, e.g. "X:4 O:0"
+///
+/// Only the format of the moves is checked, not whether they can be played in order.
+///
+/// # Arguments
+///
+/// * 'notation' - The transcript, using the internal X and O
+fn parse_notation(notation: &str) -> Result<Vec<(Sign, usize)>, CreateError> {
+    notation
+        .split_whitespace()
+        .map(|token| {
+            let invalid = || CreateError::InvalidNotation(token.to_string());
+            let (sign, index) = token.split_once(':').ok_or_else(invalid)?;
+            let sign = Sign::try_from(sign).map_err(|_| invalid())?;
+            match index.parse::<usize>() {
+                Ok(index) if index < 9 => Ok((sign, index)),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
 }
 
 /// Makes a computer move. This function only updates the board and does not check being used
 /// out of turn etc. Making this function public could break game logic.
 ///
-/// Checks which positions are open ('-') in the string, and places their indexes into an array
-/// A random number in that range is then generated and the move made in that slot
+/// The cell is chosen by the strategy, any random choices are made with the given generator.
 ///
-/// Returns updated board
+/// Returns updated board, the index of the cell the computer played and how the strategy chose it
 ///
 /// # Arguments
 ///
 /// * 'current_board' - Representation of the board as it is before a computer move is made
-fn make_computer_move(mut current_board: String, computer_sign: &str) -> String {
-    // Checks which positions are open ('-') in the string, and places their indexes into an array
-    // A random number in that range is then generated and the move made in that slot
-    let mut empty_spaces = vec![];
-    for (i, char) in current_board.clone().chars().enumerate() {
-        if char == '-' {
-            empty_spaces.push(i);
+///
+/// * 'computer_sign' - Sign the computer places
+///
+/// * 'strategy' - Strategy the cell is chosen with
+///
+/// * 'rng' - Random number generator the strategy makes its random choices with
+///
+/// # Panics
+/// Panics if the board has no empty cells, callers end the game on a full board before replying
+fn make_computer_move(
+    current_board: String,
+    computer_sign: Sign,
+    strategy: &dyn MoveStrategy,
+    rng: &mut StdRng,
+) -> (String, usize, &'static str) {
+    let (index, kind) = strategy.choose(&current_board, computer_sign, rng);
+    (place(&current_board, index, computer_sign), index, kind)
+}
+
+/// Adds an occurrence of the board to the counts of the positions of a game, see Game::repetitions
+///
+/// # Arguments
+///
+/// * 'positions' - Amount of times each position has occurred, keyed by canonical form
+///
+/// * 'board' - Representation of the board
+fn count_position(positions: &mut HashMap<String, u32>, board: &str) {
+    *positions.entry(canonical(board).0).or_insert(0) += 1;
+}
+
+/// Name of the strategy used by games that were stored without one
+fn default_strategy() -> String {
+    String::from(DEFAULT_STRATEGY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a game from a creation request given as JSON, with the default configuration and strategies
+    fn create(request: &str) -> Game {
+        let player_list = PlayerList {
+            player_map: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let new_game: NewGame = rocket::serde::json::from_str(request).unwrap();
+        Game::new(
+            &new_game,
+            &player_list,
+            &Config::from_env(),
+            &Strategies::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn seeded_computer_x_bias_sets_the_sign_distribution() {
+        let computer_x = |bias: f64| {
+            (0..400)
+                .filter(|seed| {
+                    let request = format!(
+                        r#"{{"board": "---------", "seed": {}, "computer_x_bias": {}}}"#,
+                        seed, bias
+                    );
+                    create(&request).player_sign() == Sign::O
+                })
+                .count()
+        };
+
+        assert_eq!(computer_x(0.0), 0);
+        assert_eq!(computer_x(1.0), 400);
+        let fair = computer_x(0.5);
+        assert!((160..=240).contains(&fair), "{} of 400", fair);
+        let biased = computer_x(0.8);
+        assert!((280..=360).contains(&biased), "{} of 400", biased);
+        // Seeded games flip the same coin every time
+        assert_eq!(computer_x(0.8), biased);
+    }
+
+    #[test]
+    fn idle_games_are_forfeited_by_the_sign_kept_on_the_game() {
+        // The PlayerList the games were created with is gone, only the games know the player's sign
+        let mut as_x = create(r#"{"board": "XX-OO----", "resume": true}"#);
+        let mut as_o = create(r#"{"board": "X--------", "resume": true}"#);
+        as_x.updated_at = 0;
+        as_o.updated_at = 0;
+        let game_list = GameList {
+            list: Arc::new(Mutex::new(HashMap::from([
+                (String::from("x"), as_x),
+                (String::from("o"), as_o),
+            ]))),
+        };
+
+        let mut forfeited = game_list.forfeit_idle_games(60, false);
+
+        forfeited.sort();
+        assert_eq!(forfeited, ["o", "x"]);
+        let list = game_list.list.lock().unwrap();
+        assert_eq!(list["x"].get_status().as_deref(), Some("O_WON"));
+        assert_eq!(list["o"].get_status().as_deref(), Some("X_WON"));
+    }
+
+    #[test]
+    fn win_conditions_set_the_status_from_the_board() {
+        let mut game = create(r#"{"board": "---------", "analysis": true}"#);
+        let cases = [
+            ("XXXOO----", Some("X_WON")),
+            ("OX-OX-O-X", Some("O_WON")),
+            ("X-OXO-O-X", Some("O_WON")),
+            ("XOXOXO--X", Some("X_WON")),
+            ("XOXXOOOXX", Some("DRAW")),
+            ("XO--X--O-", None),
+        ];
+
+        for (board, ended) in cases {
+            game.set_board(String::from(board));
+            assert_eq!(game.check_win_conditions(), ended.is_some(), "{}", board);
+            let status = ended.unwrap_or("RUNNING");
+            assert_eq!(game.get_status().as_deref(), Some(status), "{}", board);
+        }
+    }
+
+    #[test]
+    fn stored_board_is_packed_and_read_back_unchanged() {
+        let mut game = create(r#"{"board": "X---O----", "resume": true}"#);
+        assert!(matches!(game.board, PackedBoard::Packed { .. }));
+        assert_eq!(game.get_board(), "X---O----");
+
+        game.set_board(String::from("XX--O----"));
+        let json = rocket::serde::json::to_value(&game).unwrap();
+        assert_eq!(json["board"], "XX--O----");
+        let read: Game = rocket::serde::json::from_value(json).unwrap();
+        assert_eq!(read.board, game.board);
+        assert_eq!(read.get_board(), "XX--O----");
+    }
+
+    #[test]
+    fn move_outcome_carries_the_reply_and_whether_the_game_ended() {
+        let config = Config::from_env();
+        let strategies = Strategies::default();
+        let mut game = create(r#"{"board": "X---O----", "resume": true, "strategy": "block"}"#);
+
+        let normal = game
+            .make_move(String::from("XX--O----"), &config, &strategies)
+            .unwrap();
+        assert!(!normal.game_over);
+        assert_eq!(game.get_board(), "XXO-O----");
+        assert_eq!(game.get_status().as_deref(), Some("RUNNING"));
+        assert_eq!(
+            normal.computer_move,
+            Some(Move::new(2, Sign::O, "COMPUTER"))
+        );
+
+        let mut game = create(r#"{"board": "XX-OO----", "resume": true}"#);
+        let winning = game
+            .make_move(String::from("XXXOO----"), &config, &strategies)
+            .unwrap();
+        assert!(winning.game_over);
+        assert_eq!(game.get_status().as_deref(), Some("X_WON"));
+        assert_eq!(winning.computer_move, None);
+    }
+
+    #[test]
+    fn moves_are_validated_with_the_sign_kept_on_the_game() {
+        let config = Config::from_env();
+        let strategies = Strategies::default();
+        // The PlayerList the game was created with is gone, only the game knows the player is O
+        let mut game = create(r#"{"board": "X--------", "resume": true}"#);
+        assert_eq!(game.player_sign(), Sign::O);
+
+        assert!(matches!(
+            game.make_move(String::from("XX-------"), &config, &strategies),
+            Err(MoveError::InvalidMove)
+        ));
+        game.make_move(String::from("XO-------"), &config, &strategies)
+            .unwrap();
+        assert_eq!(game.get_board().matches('X').count(), 2);
+
+        let json = rocket::serde::json::to_string(&game).unwrap();
+        assert!(!json.contains("player_sign"));
+    }
+
+    #[test]
+    fn symmetric_positions_revisited_after_undo_are_counted_as_repetitions() {
+        let strategies = Strategies::default();
+        let mut game = create(r#"{"board": "---------", "sign": "X", "seed": 7}"#);
+        game.make_move(String::from("X--------"), &Config::from_env(), &strategies)
+            .unwrap();
+        let mut seen = vec![game.get_board()];
+
+        // The computer replies again after each undo, eventually in a cell mirroring an earlier reply
+        for _ in 0..30 {
+            game.undo_computer_move(true, &strategies).unwrap();
+            seen.push(game.get_board());
         }
+
+        let current = canonical(&game.get_board()).0;
+        let expected = seen
+            .iter()
+            .filter(|board| canonical(board).0 == current)
+            .count();
+        assert_eq!(game.repetitions(), expected as u32);
+        let revisited = seen.iter().any(|board| {
+            seen.iter()
+                .any(|other| other != board && canonical(other).0 == canonical(board).0)
+        });
+        assert!(revisited, "no reply mirrored another: {:?}", seen);
     }
 
-    // Generating random number to choose the slot to make computer move
-    let mut rng = rand::thread_rng();
-    let random_choice = rng.gen_range(0..empty_spaces.len());
+    #[test]
+    fn autoplay_after_undo_without_reply_starts_with_the_computer() {
+        let mut game =
+            create(r#"{"board": "---------", "sign": "X", "autoplay": true, "seed": 1}"#);
+        game.make_move(
+            String::from("----X----"),
+            &Config::from_env(),
+            &Strategies::default(),
+        )
+        .unwrap();
+        game.undo_computer_move(false, &Strategies::default())
+            .unwrap();
 
-    // Making computer move
-    let index_to_be_replaced = empty_spaces[random_choice];
-    current_board.replace_range(
-        index_to_be_replaced..index_to_be_replaced + 1,
-        computer_sign,
-    );
+        game.autoplay().unwrap();
 
-    //returning updated board
-    current_board
+        let history = game.get_history();
+        assert_eq!(history[1].sign, Sign::O);
+        assert_eq!(history[1].by, "COMPUTER");
+        for pair in history.windows(2) {
+            assert_ne!(pair[0].sign, pair[1].sign);
+        }
+        assert!(game.game_over);
+    }
 }
@@ -0,0 +1,96 @@
+use crate::config::Config;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Request, State};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Keeps count of requests made by each client IP within a fixed window.
+///
+/// The map is wrapped in a Mutex to allow it to be handled asynchronously by the request guard.
+pub struct RateLimiter {
+    /// Start of the current window and amount of requests made within it, per client IP
+    pub counters: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+
+    /// Maximum amount of requests allowed per window
+    pub limit: u32,
+
+    /// Length of a window
+    pub window: Duration,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter with no recorded requests
+    ///
+    /// # Arguments
+    ///
+    /// * 'limit' - Maximum amount of requests allowed per window
+    ///
+    /// * 'window' - Length of a window in seconds
+    pub fn new(limit: u32, window: u64) -> RateLimiter {
+        RateLimiter {
+            counters: Mutex::new(HashMap::new()),
+            limit,
+            window: Duration::from_secs(window),
+        }
+    }
+
+    /// Records a request from the client and returns whether it is within the limit
+    ///
+    /// Resets the client's counter if its window has elapsed, and forgets the clients whose windows have
+    /// elapsed so the map doesn't grow with every client ever seen.
+    ///
+    /// # Arguments
+    ///
+    /// * 'client' - IP address of the client making the request
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn check(&self, client: IpAddr) -> bool {
+        let mut counters = self.counters.lock().unwrap();
+        let now = Instant::now();
+        // Window has passed, the client starts a new one
+        counters.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+        let entry = counters.entry(client).or_insert((now, 0));
+        entry.1 += 1;
+
+        entry.1 <= self.limit
+    }
+}
+
+/// Request guard that rejects requests with 429 Too Many Requests once the client has exceeded
+/// the rate limit.
+///
+/// Clients are identified by the address of the connection. The X-Real-IP header is only used when
+/// the server is configured to trust a proxy setting it, as clients could otherwise rotate it freely.
+/// Requests without a known remote address are let through.
+pub struct RateLimited;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RateLimited {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let limiter = match req.guard::<&State<RateLimiter>>().await {
+            Outcome::Success(limiter) => limiter,
+            _ => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+
+        let trust_proxy = match req.guard::<&State<Config>>().await {
+            Outcome::Success(config) => config.trust_proxy,
+            _ => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+        let client = if trust_proxy {
+            req.client_ip()
+        } else {
+            req.remote().map(|remote| remote.ip())
+        };
+
+        match client {
+            Some(ip) if !limiter.check(ip) => Outcome::Failure((Status::TooManyRequests, ())),
+            _ => Outcome::Success(RateLimited),
+        }
+    }
+}
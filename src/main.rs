@@ -1,19 +1,77 @@
+// Rocket handlers take their state and request guards as arguments, which adds up quickly
+#![allow(clippy::too_many_arguments)]
+// The OpenAPI document is a single json! invocation that outgrew the default macro recursion limit
+#![recursion_limit = "256"]
+
+mod ai;
+mod auth;
+mod board;
+mod compact;
+mod conditional;
+mod config;
+mod cors;
+mod deadline;
+mod formats;
 mod game;
+mod limiter;
+mod matches;
+mod openapi;
+mod packed;
+mod sign;
+mod strategy;
+mod tombstones;
+mod tournament;
+mod transcript;
+mod watch;
 
 #[macro_use]
 extern crate rocket;
 
-use crate::game::{Game, GameList, PlayerList};
+use crate::ai::{distance_to_end, evaluate, ranked_moves, tree, TreeNode, MAX_TREE_DEPTH};
+use crate::auth::{start_session, Admin, MoveToken, OwnerToken};
+use crate::board::{
+    board_to_bitboards, canonical, classify_line, custom_marks, internal_marks,
+    reachable_positions, side_to_move, trim_board, winning_line, LineKind, LINES,
+};
+use crate::compact::CompactState;
+use crate::conditional::IfMatch;
+use crate::config::Config;
+use crate::cors::Cors;
+use crate::formats::BoardFormat;
+use crate::game::{
+    evaluate_board, fork_cells, prepare_board, validate_move, validate_position, winning_cells,
+    CreateError, Game, GameList, GameStatus, MoveError, NewGame, PlayerList, TokenList,
+};
+use crate::limiter::{RateLimited, RateLimiter};
+use crate::matches::{Match, MatchList, NewMatch, MAX_BEST_OF};
+use crate::sign::Sign;
+use crate::strategy::{Strategies, DEFAULT_STRATEGY};
+use crate::tombstones::Tombstones;
+use crate::tournament::{Tournament, TournamentResult};
+use crate::watch::Watchers;
 
-use rocket::http::{ContentType, Status};
+use rocket::fairing::AdHoc;
+use rocket::http::{ContentType, CookieJar, Header, Method, Status};
+use rocket::request::{self, FromRequest};
 use rocket::response::Responder;
 
-use rocket::serde::json::Json;
-use rocket::{response, Request, Response, State};
+use json_patch::Patch;
+use log::{error, info, warn};
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::{self, Json, Value};
+use rocket::tokio::select;
+use rocket::tokio::time::{sleep, timeout};
+use rocket::{response, Build, Request, Response, Rocket, Shutdown, State};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io;
+use std::mem::size_of;
 
-use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use url::Url;
+use uuid::Uuid;
 
 /// Container for HTTP responses
 struct APIResponse<T> {
@@ -23,247 +81,6673 @@ struct APIResponse<T> {
     status: Status,
 }
 
+/// Response body ready for serialization
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Body<'a, T> {
+    /// Body using the X and O used internally
+    Internal(&'a T),
+    /// Body converted to JSON with the configured marks swapped in
+    Localized(Value),
+}
+
+/// Payload of a successful response, wrapped with metadata when the envelope is configured
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Payload<B> {
+    /// The body as it is
+    Raw(B),
+    /// The body under "data" along with metadata about the response
+    Enveloped { data: B, meta: Meta },
+}
+
+/// Metadata about a response sent in the envelope
+#[derive(Serialize)]
+struct Meta {
+    /// Unique ID of the request, generated for each response
+    request_id: String,
+    /// Unix time in seconds when the response was built
+    timestamp: u64,
+}
+
+impl Meta {
+    /// Builds the metadata of a response built now
+    fn now() -> Meta {
+        Meta {
+            request_id: Uuid::new_v4().to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Prepares a response body for serialization, replacing X and O with custom marks when configured
+///
+/// Bodies are passed through as they are with the default marks, keeping their field order.
+///
+/// # Arguments
+///
+/// * 'body' - Response body
+///
+/// * 'req' - The request being responded to
+fn localized<'a, T: Serialize>(
+    body: &'a T,
+    req: &Request<'_>,
+) -> Result<Body<'a, T>, json::serde_json::Error> {
+    let marks = req
+        .rocket()
+        .state::<Config>()
+        .map_or(('X', 'O'), |config| config.marks);
+    if marks == ('X', 'O') {
+        return Ok(Body::Internal(body));
+    }
+    let mut value = json::to_value(body)?;
+    localize_marks(&mut value, marks);
+    Ok(Body::Localized(value))
+}
+
+/// Replaces the X and O used internally with the configured marks in a response body
+///
+/// Only boards and signs are replaced: "board" strings, the cells of "grid" arrays and the "sign" and
+/// "to_move" fields, at any depth. Statuses such as "X_WON" keep naming the sides X and O.
+///
+/// # Arguments
+///
+/// * 'value' - Response body as JSON
+///
+/// * 'marks' - Marks of the first and second side
+fn localize_marks(value: &mut Value, marks: (char, char)) {
+    if marks == ('X', 'O') {
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match (key.as_str(), field) {
+                    ("board" | "sign" | "to_move", Value::String(text)) => {
+                        *text = custom_marks(text, marks)
+                    }
+                    ("grid", Value::Array(rows)) => {
+                        for cell in rows.iter_mut().flat_map(|row| row.as_array_mut()).flatten() {
+                            if let Value::String(text) = cell {
+                                *text = custom_marks(text, marks);
+                            }
+                        }
+                    }
+                    (_, field) => localize_marks(field, marks),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                localize_marks(item, marks);
+            }
+        }
+        _ => {}
+    }
+}
+
 // Response build structure modelled after https://stackoverflow.com/a/70563341
 
-impl<'r, T: serde::Serialize> Responder<'r, 'r> for APIResponse<T> {
+impl<'r, 'o: 'r, T: serde::Serialize> Responder<'r, 'o> for APIResponse<T> {
     /// Builds response
-    fn respond_to(self, req: &Request) -> response::Result<'r> {
-        Response::build_from(self.json.respond_to(req).unwrap())
-            .status(self.status)
-            .header(ContentType::JSON)
-            .ok()
+    ///
+    /// Clients preferring YAML with `Accept: application/yaml` receive the body serialized as YAML,
+    /// everything else receives JSON.
+    /// Successful responses are wrapped in an envelope with metadata if configured, errors never are.
+    /// GET requests with the query parameter `pretty=true` receive indented JSON for easier reading
+    /// when debugging, all other JSON responses are compact.
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let body = match localized(&self.json.0, req) {
+            Ok(body) => body,
+            Err(e) => {
+                println!("{}", e);
+                return Err(Status::InternalServerError);
+            }
+        };
+        let envelope = req
+            .rocket()
+            .state::<Config>()
+            .is_some_and(|config| config.response_envelope);
+        let body = if envelope && self.status.class().is_success() {
+            Payload::Enveloped {
+                data: body,
+                meta: Meta::now(),
+            }
+        } else {
+            Payload::Raw(body)
+        };
+
+        let yaml = req.accept().is_some_and(|accept| {
+            let media_type = accept.preferred().media_type();
+            media_type.top() == "application"
+                && (media_type.sub() == "yaml" || media_type.sub() == "x-yaml")
+        });
+        let pretty = req.method() == Method::Get
+            && req
+                .query_value::<bool>("pretty")
+                .and_then(|value| value.ok())
+                .unwrap_or(false);
+
+        let (body, content_type) = if yaml {
+            (
+                serde_yaml::to_string(&body).map_err(|e| e.to_string()),
+                ContentType::new("application", "yaml"),
+            )
+        } else if pretty {
+            (
+                json::to_pretty_string(&body).map_err(|e| e.to_string()),
+                ContentType::JSON,
+            )
+        } else {
+            (
+                json::to_string(&body).map_err(|e| e.to_string()),
+                ContentType::JSON,
+            )
+        };
+
+        match body {
+            Ok(body) => Response::build_from(body.respond_to(req).unwrap())
+                .status(self.status)
+                .header(content_type)
+                .ok(),
+            Err(e) => {
+                println!("{}", e);
+                Err(Status::InternalServerError)
+            }
+        }
     }
 }
 
-/// Base index response
+/// Wraps a response to add extra headers to it
+struct WithHeaders<R> {
+    /// Response the headers are added to
+    inner: R,
+    /// Headers to add
+    headers: Vec<Header<'static>>,
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for WithHeaders<R> {
+    /// Builds the inner response and sets the headers on it
+    fn respond_to(self, req: &'r Request) -> response::Result<'o> {
+        let mut response = self.inner.respond_to(req)?;
+        for header in self.headers {
+            response.set_header(header);
+        }
+        Ok(response)
+    }
+}
+
+/// Error payload returned in the body of failed requests
+#[derive(Serialize)]
+struct ErrorMessage {
+    /// Description of what went wrong
+    error: String,
+}
+
+impl ErrorMessage {
+    /// Builds an error response with the message as its payload
+    ///
+    /// # Arguments
+    ///
+    /// * 'status' - HTTP Response status code
+    ///
+    /// * 'error' - Description of what went wrong
+    fn response(status: Status, error: impl ToString) -> APIResponse<ErrorMessage> {
+        APIResponse {
+            json: Json(ErrorMessage {
+                error: error.to_string(),
+            }),
+            status,
+        }
+    }
+}
+
+/// Handles requests with bodies over the configured JSON limit
 ///
-/// Unused in API context but left here to avoid not having any kind of index
-#[get("/")]
-fn index() -> &'static str {
-    "Nothing here go to /games"
+/// Returns a JSON error instead of Rocket's default HTML page
+#[catch(413)]
+fn payload_too_large() -> APIResponse<ErrorMessage> {
+    ErrorMessage::response(Status::PayloadTooLarge, "Request body is too large")
 }
 
-/// Gets a list of all games and returns them as as an array
+/// Summary of an admin reset
+#[derive(Serialize)]
+struct ResetSummary {
+    /// Amount of games removed from the store
+    removed: usize,
+}
+
+/// Summary of an admin garbage collection
+#[derive(Serialize)]
+struct GcSummary {
+    /// Amount of player sign choices removed for games that no longer exist
+    removed: usize,
+    /// Amount of read, owner and move token entries removed for games that no longer exist
+    removed_tokens: usize,
+}
+
+/// A game in an export of the store along with the sign of its player, which isn't kept in the game
+#[derive(Serialize, Deserialize)]
+struct ExportedGame {
+    /// The game
+    #[serde(flatten)]
+    game: Game,
+    /// Sign the player places
+    player_sign: Sign,
+}
+
+/// Summary of an admin import
+#[derive(Serialize)]
+struct ImportSummary {
+    /// Amount of games loaded into the store
+    imported: usize,
+    /// Whether the store was emptied before loading the games
+    replaced: bool,
+}
+
+/// Effective runtime configuration of the server, with secrets redacted
+#[derive(Serialize)]
+struct EffectiveConfig<'a> {
+    /// URL the links to games and matches are built from
+    base_url: &'static str,
+    /// Strategy games use when none is chosen
+    default_strategy: &'static str,
+    /// Amount of cells on a board
+    board_size: usize,
+    /// The configuration read from the environment
+    #[serde(flatten)]
+    config: &'a Config,
+}
+
+/// Operational metrics of the in-memory store
+#[derive(Serialize)]
+struct StoreStats {
+    /// Amount of games in the store, archived games included
+    games: usize,
+    /// Amount of archived games
+    archived_games: usize,
+    /// Amount of player sign choices
+    players: usize,
+    /// Amount of read-only, owner and move tokens
+    tokens: usize,
+    /// Approximate memory used by the games, player sign choices and tokens in bytes
+    approximate_bytes: usize,
+}
+
+/// Reports the size of the in-memory store for operational visibility.
 ///
+/// Requires the API key. The memory figure is an estimate from the sizes
+/// of the stored values and their strings, allocator and map overhead is not included.
 ///
 /// # Arguments
 ///
 /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
 ///
-#[get("/games")]
-fn all_games(game_list: &State<GameList>) -> APIResponse<Vec<Game>> {
-    let lock = game_list.inner(); // Getting state
-    let guard = lock.list.lock().unwrap();
-    let all_games = guard.values().cloned().collect::<Vec<Game>>();
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'tokens' - Maintains a map of the read-only tokens of each game in a mutex to handle async requests
+///
+/// * '_admin' - Request guard checking the API key
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/admin/stats")]
+fn store_stats(
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    tokens: &State<TokenList>,
+    _admin: Admin,
+) -> APIResponse<StoreStats> {
+    // Each lock is held only while its map is measured
+    let (games, archived_games, game_bytes) = {
+        let games = game_list.inner().list.lock().unwrap();
+        let bytes = games
+            .iter()
+            .map(|(id, game)| id.capacity() + game.approximate_size())
+            .sum::<usize>();
+        let archived = games.values().filter(|game| game.is_archived()).count();
+        (games.len(), archived, bytes)
+    };
+    let (players, player_bytes) = {
+        let players = player_signs.inner().player_map.lock().unwrap();
+        let bytes = players
+            .keys()
+            .map(|id| size_of::<String>() + id.capacity() + size_of::<Sign>())
+            .sum::<usize>();
+        (players.len(), bytes)
+    };
+    let (token_count, token_bytes) = [&tokens.read_tokens, &tokens.owner_tokens]
+        .iter()
+        .map(|map| {
+            let map = map.lock().unwrap();
+            let bytes = map
+                .iter()
+                .map(|(key, value)| 2 * size_of::<String>() + key.capacity() + value.capacity())
+                .sum::<usize>();
+            (map.len(), bytes)
+        })
+        .fold((0, 0), |(count, bytes), (len, size)| {
+            (count + len, bytes + size)
+        });
+    let (move_token_count, move_token_bytes) = {
+        let map = tokens.move_tokens.lock().unwrap();
+        let bytes = map
+            .iter()
+            .map(|(key, (x, o))| {
+                3 * size_of::<String>() + key.capacity() + x.capacity() + o.capacity()
+            })
+            .sum::<usize>();
+        (2 * map.len(), bytes)
+    };
 
     APIResponse {
-        json: Json(all_games),
+        json: Json(StoreStats {
+            games,
+            archived_games,
+            players,
+            tokens: token_count + move_token_count,
+            approximate_bytes: game_bytes + player_bytes + token_bytes + move_token_bytes,
+        }),
         status: Status::Ok,
     }
 }
 
-/// Returns the current game object based on its ID which is parsed from the URL.
+/// Reports the configuration the server is running with, as read from the environment at launch
+///
+/// Requires the API key. The API key itself is never reported, only
+/// whether one is set.
 ///
 /// # Arguments
 ///
-/// * 'id' - Parsed from the URL, ID of the game
+/// * 'config' - Runtime configuration of the API
+///
+/// * '_admin' - Request guard checking the API key
+#[get("/admin/config")]
+fn server_config<'a>(config: &'a State<Config>, _admin: Admin) -> APIResponse<EffectiveConfig<'a>> {
+    APIResponse {
+        json: Json(EffectiveConfig {
+            base_url: BASE_URL,
+            default_strategy: DEFAULT_STRATEGY,
+            board_size: 9,
+            config: config.inner(),
+        }),
+        status: Status::Ok,
+    }
+}
+
+/// Wipes all games, player sign choices, tokens, matches, deleted game records and rate limit counters
+/// from the store.
+///
+/// Useful for test harnesses that need a clean state without restarting the process.
+/// Clients waiting for a game to change are woken up and answered as if the game was deleted.
+/// Only the configuration and the registered strategies are kept.
+/// Requires the API key.
+///
+/// Returns the amount of games removed
+///
+/// # Arguments
 ///
 /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
 ///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'tokens' - Maintains a map of the read-only tokens of each game in a mutex to handle async requests
+///
+/// * 'match_list' - Maintains a map of all matches in a mutex to handle asynchronous requests
+///
+/// * 'tombstones' - Record of recently deleted games
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'limiter' - Request counters of each client IP
+///
+/// * '_admin' - Request guard checking the API key
+///
 /// # Panics
 /// May panic if the the function is unable to open up the mutex
-#[get("/games/<id>")]
-fn game_board(id: String, game_list: &State<GameList>) -> Result<APIResponse<Game>, Status> {
-    let lock = game_list.inner(); // Getting state
-    let current_game;
-    if lock.list.lock().unwrap().contains_key(&*id) {
-        // If id exists, get the game
-        let guard = lock.list.lock().unwrap();
-        let map_entry = guard.get(&*id);
-        match map_entry {
-            Some(game) => current_game = game,
-            _ => {
-                return Err(Status::InternalServerError); // Should be unreachable;
-            }
-        }
-        return Ok(APIResponse {
-            json: Json(current_game.clone()),
-            status: Status::Ok,
-        });
+#[post("/admin/reset")]
+fn reset_store(
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    tokens: &State<TokenList>,
+    match_list: &State<MatchList>,
+    tombstones: &State<Tombstones>,
+    watchers: &State<Watchers>,
+    limiter: &State<RateLimiter>,
+    _admin: Admin,
+) -> APIResponse<ResetSummary> {
+    let mut games = game_list.inner().list.lock().unwrap();
+    let mut players = player_signs.inner().player_map.lock().unwrap();
+
+    let removed = games.len();
+    games.clear();
+    players.clear();
+    tokens.read_tokens.lock().unwrap().clear();
+    tokens.move_tokens.lock().unwrap().clear();
+    tokens.owner_tokens.lock().unwrap().clear();
+    match_list.list.lock().unwrap().clear();
+    tombstones.deleted.lock().unwrap().clear();
+    limiter.counters.lock().unwrap().clear();
+    // Woken up after the games are gone, so waiters see them deleted
+    watchers.clear();
+
+    APIResponse {
+        json: Json(ResetSummary { removed }),
+        status: Status::Ok,
     }
-    Err(Status::NotFound)
 }
 
-/// Handles the put request to make a new move to a specified game
+/// Removes the player sign choices and tokens of games that no longer exist.
 ///
-/// Gets the active game by id parsed from the URL and tries to make the user defined moved
-/// which is the payload in the PUT request.
+/// Deleting a game removes its sign choice and tokens, this reconciles entries left behind otherwise.
+/// Requires the API key.
 ///
-/// Returns the updated game board with the computer's response move updated to the board
+/// Returns the amount of sign choices and token entries removed
 ///
 /// # Arguments
 ///
-/// * 'id' - Parsed from the URL, ID of the game
-///
 /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
 ///
-/// * 'game' - Payload in the PUT request, contains to game object with an updated board. (Player move)
-///
 /// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
 ///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// * '_admin' - Request guard checking the API key
+///
 /// # Panics
 /// May panic if the the function is unable to open up the mutex
-#[put("/games/<id>", format = "json", data = "<game>")]
-fn put_player_move(
-    id: String,
+#[post("/admin/gc")]
+fn collect_garbage(
     game_list: &State<GameList>,
-    game: Json<Game>,
     player_signs: &State<PlayerList>,
-) -> Result<APIResponse<Game>, Status> {
-    let game_list_lock = game_list.inner();
-    let submitted_new_game_state = game;
-    let current_game;
+    tokens: &State<TokenList>,
+    _admin: Admin,
+) -> APIResponse<GcSummary> {
+    let games = game_list.inner().list.lock().unwrap();
+    let mut players = player_signs.inner().player_map.lock().unwrap();
 
-    let player_list_lock = player_signs.inner();
+    let before = players.len();
+    players.retain(|id, _| games.contains_key(id));
+    let removed_tokens = tokens.retain_existing(|id| games.contains_key(id));
 
-    // if game exists
-    if game_list_lock.list.lock().unwrap().contains_key(&*id) {
-        let mut guard = game_list_lock.list.lock().unwrap();
-        let map_entry = guard.get_mut(&*id);
+    APIResponse {
+        json: Json(GcSummary {
+            removed: before - players.len(),
+            removed_tokens,
+        }),
+        status: Status::Ok,
+    }
+}
 
-        match map_entry {
-            Some(game) => current_game = game,
-            _ => {
-                return Err(Status::InternalServerError);
-            }
-        }
-        let new_board = submitted_new_game_state.get_board().clone(); // generate new board based on moves TEMP
-        if !current_game.make_move(new_board, player_list_lock) {
-            return Err(Status::BadRequest);
-        }
-        // Maybe set status to something if needed
-        return Ok(APIResponse {
-            json: Json(current_game.clone()),
-            status: Status::Ok,
-        });
+/// Downloads every game in the store as a JSON array for backups, see import_store.
+///
+/// The games are sent as stored, with the internal X and O, along with the sign of the player.
+/// Tokens are not exported. Requires the API key.
+///
+/// # Arguments
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * '_admin' - Request guard checking the API key
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/admin/export")]
+fn export_store(
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    _admin: Admin,
+) -> WithHeaders<Json<Vec<ExportedGame>>> {
+    let games = game_list.inner().list.lock().unwrap();
+    let players = player_signs.inner().player_map.lock().unwrap();
+
+    let mut export = games
+        .iter()
+        .filter_map(|(id, game)| {
+            // Games are always stored along with their player, skipping any that aren't
+            players.get(id).map(|sign| ExportedGame {
+                game: game.clone(),
+                player_sign: *sign,
+            })
+        })
+        .collect::<Vec<ExportedGame>>();
+    export.sort_by(|a, b| a.game.get_id().cmp(b.game.get_id()));
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    WithHeaders {
+        inner: Json(export),
+        headers: vec![Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"games-{}.json\"", timestamp),
+        )],
     }
-    Err(Status::NotFound)
 }
 
-/// Creates a new game with a board as defined in the POST request payload
+/// Loads the games of an export made with export_store into the store.
 ///
-/// The handler will validate a user defined first move and provide a response move from the computer
+/// With IMPORT_REPLACE the store, including tokens, is emptied first. Otherwise the games are merged
+/// into it, replacing games with the same ID. Nothing is loaded if any game lacks an ID or is
+/// inconsistent, e.g. has a malformed board or a history that doesn't lead to it. Requires the API key.
+///
+/// Returns the amount of games loaded
 ///
 /// # Arguments
 ///
-/// * 'board' - POST request payload, contains a representation of the game board
+/// * 'export' - POST request payload, the exported games
 ///
 /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
 ///
 /// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
 ///
+/// * 'tokens' - Maintains a map of the read-only tokens of each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * '_admin' - Request guard checking the API key
+///
 /// # Panics
 /// May panic if the the function is unable to open up the mutex
-#[post("/games", format = "json", data = "<board>")]
-fn new_game(
-    board: Json<Game>,
+#[post("/admin/import", format = "json", data = "<export>")]
+fn import_store(
+    export: Json<Vec<ExportedGame>>,
     game_list: &State<GameList>,
     player_signs: &State<PlayerList>,
-) -> Result<APIResponse<Url>, Status> {
-    // New getting board from the game object in the request
-    let new_board = board.get_board().clone();
+    tokens: &State<TokenList>,
+    config: &State<Config>,
+    _admin: Admin,
+) -> Result<APIResponse<ImportSummary>, APIResponse<ErrorMessage>> {
+    let export = export.into_inner();
+    for exported in &export {
+        let id = match exported.game.get_id() {
+            Some(id) => id,
+            None => {
+                return Err(ErrorMessage::response(
+                    Status::BadRequest,
+                    "Unable to import games: a game has no id",
+                ))
+            }
+        };
+        // Inconsistent games would make later moves panic while the store is locked
+        if let Err(reason) = exported.game.validate_restored(exported.player_sign) {
+            return Err(ErrorMessage::response(
+                Status::UnprocessableEntity,
+                format!("Unable to import game {}: {}", id, reason),
+            ));
+        }
+    }
 
-    // Pulling player map in
-    let _player_map = &player_signs.inner().player_map;
+    let mut games = game_list.inner().list.lock().unwrap();
+    let mut players = player_signs.inner().player_map.lock().unwrap();
+    if config.import_replace {
+        games.clear();
+        players.clear();
+        tokens.read_tokens.lock().unwrap().clear();
+        tokens.move_tokens.lock().unwrap().clear();
+        tokens.owner_tokens.lock().unwrap().clear();
+    }
 
-    // Creating new game object with the board
-    let try_new_game = Game::new(new_board, player_signs);
-    let new_game;
-    match try_new_game {
-        Ok(valid_game) => new_game = valid_game,
-        Err(e) => {
-            println!("{}", e);
-            return Err(Status::BadRequest);
-        }
+    let imported = export.len();
+    for ExportedGame {
+        mut game,
+        player_sign,
+    } in export
+    {
+        let id = game.get_id().clone().unwrap(); // Checked above
+        game.restart_clock();
+        game.rebuild_positions();
+        game.restore_outcome(player_sign);
+        game.set_player_sign(player_sign);
+        players.insert(id.clone(), player_sign);
+        games.insert(id, game);
     }
 
-    // Getting game id for use in map of games and url
-    let id = new_game.get_id().clone().unwrap();
-    let id_for_redirect = id.clone();
+    Ok(APIResponse {
+        json: Json(ImportSummary {
+            imported,
+            replaced: config.import_replace,
+        }),
+        status: Status::Ok,
+    })
+}
 
-    // Adding game to map
-    let lock = game_list.inner();
-    lock.list.lock().unwrap().insert(id, new_game);
+/// Replaces the board of a game with a legal position for setting up tests.
+///
+/// The status is recomputed from the new board and the computer does not reply.
+/// Requires the API key.
+///
+/// Returns the updated game
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'board' - PUT request payload, contains a representation of the new board
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * '_admin' - Request guard checking the API key
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[put("/games/<id>/board", format = "json", data = "<board>")]
+fn set_game_board(
+    id: String,
+    board: Json<Game>,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+    watchers: &State<Watchers>,
+    _admin: Admin,
+) -> Result<APIResponse<Game>, APIResponse<ErrorMessage>> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
 
-    // redirecting to game
-    // Would be set to actual host adress in prod with env variable
-    let current_host;
-    match Url::parse("http://127.0.0.1:8000/") {
-        Ok(host_url) => current_host = host_url,
-        Err(e) => {
-            println!("{}", e);
-            return Err(Status::InternalServerError);
-        }
+    if let Err(e) = current_game.set_position(&board.get_board(), config) {
+        let status = create_error_status(&e);
+        return Err(ErrorMessage::response(
+            status,
+            format!("Unable to set board: {}", e.reason()),
+        ));
     }
+    watchers.notify(&id);
 
-    let game_url;
-    match current_host.join(&*format!("../games/{}", id_for_redirect)) {
-        Ok(url) => game_url = url,
-        Err(e) => {
-            println!("{}", e);
-            return Err(Status::InternalServerError);
-        }
-    }
     Ok(APIResponse {
-        json: Json(game_url),
-        status: Status::Created,
+        json: Json(current_game.clone()),
+        status: Status::Ok,
     })
 }
 
-/// Deletes a game from the list of games and returns it.
+/// Payload of a status change request
+#[derive(Deserialize)]
+struct StatusUpdate {
+    /// The new status, "RUNNING", "X_WON", "O_WON" or "DRAW"
+    status: String,
+}
+
+/// Sets the status of a game without touching the board, for driving clients through end states in tests.
+///
+/// Requires the API key.
 ///
 /// # Arguments
 ///
 /// * 'id' - Parsed from the URL, ID of the game
 ///
+/// * 'status' - Payload in the PUT request, contains the new status
+///
 /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
 ///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * '_admin' - Request guard checking the API key
+///
 /// # Panics
 /// May panic if the the function is unable to open up the mutex
-#[delete("/games/<id>")]
-fn delete_game(id: String, game_list: &State<GameList>) -> Result<APIResponse<Game>, Status> {
-    let lock = game_list.inner();
-    let mut list = lock.list.lock().unwrap();
-    let delete = list.remove(&*id);
+#[put("/games/<id>/status", format = "json", data = "<status>")]
+fn set_game_status(
+    id: String,
+    status: Json<StatusUpdate>,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    _admin: Admin,
+) -> Result<APIResponse<Game>, APIResponse<ErrorMessage>> {
+    let game_status = match GameStatus::try_from(status.status.as_str()) {
+        Ok(game_status) => game_status,
+        Err(_) => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                format!(
+                    "Unable to set status: '{}' is not one of RUNNING, X_WON, O_WON or DRAW",
+                    status.status
+                ),
+            ))
+        }
+    };
 
-    match delete {
-        Some(game) => Ok(APIResponse {
-            json: Json(game),
-            status: Status::Ok,
-        }),
-        None => Err(Status::NotFound),
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+    current_game.force_status(game_status);
+    watchers.notify(&id);
+
+    Ok(APIResponse {
+        json: Json(current_game.clone()),
+        status: Status::Ok,
+    })
+}
+
+/// Description of the API served at the root
+#[derive(Serialize)]
+struct ApiInfo {
+    /// Name of the package
+    name: &'static str,
+    /// Version of the package
+    version: &'static str,
+    /// Method and path of every mounted route
+    endpoints: Vec<String>,
+}
+
+/// Request guard listing the method and path of every mounted route
+///
+/// Never fails, the routes are read from the running Rocket instance.
+struct Endpoints(Vec<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Endpoints {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let mut routes: Vec<_> = req.rocket().routes().collect();
+        routes.sort_by_key(|route| (route.uri.to_string(), route.method.as_str()));
+        let endpoints = routes
+            .iter()
+            .map(|route| format!("{} {}", route.method, route.uri))
+            .collect();
+        request::Outcome::Success(Endpoints(endpoints))
     }
 }
 
-#[launch]
-fn rocket() -> _ {
-    // Launching rocket
-    rocket::build()
-        .manage(GameList {
-            list: Mutex::new(HashMap::new()),
-        })
-        .manage(PlayerList {
-            player_map: Mutex::new(HashMap::new()),
+/// Base index response
+///
+/// Describes the API so integrators have a discoverable root, the endpoints are read from the
+/// mounted routes so the list can't drift from the handlers.
+///
+/// # Arguments
+///
+/// * 'endpoints' - Method and path of every mounted route
+#[get("/")]
+fn index(endpoints: Endpoints) -> APIResponse<ApiInfo> {
+    APIResponse {
+        json: Json(ApiInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            endpoints: endpoints.0,
+        }),
+        status: Status::Ok,
+    }
+}
+
+/// Returns the OpenAPI 3 description of the API
+#[get("/openapi.json")]
+fn openapi_document() -> APIResponse<json::Value> {
+    APIResponse {
+        json: Json(openapi::document()),
+        status: Status::Ok,
+    }
+}
+
+/// Fields the game listing can be sorted by
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum GameSort {
+    Id,
+    CreatedAt,
+    UpdatedAt,
+    MoveCount,
+    Status,
+}
+
+impl TryFrom<&str> for GameSort {
+    type Error = ();
+
+    /// Converts a field as it's written in the sort query parameter, e.g. "created_at"
+    fn try_from(string: &str) -> Result<GameSort, ()> {
+        match string {
+            "id" => Ok(GameSort::Id),
+            "created_at" => Ok(GameSort::CreatedAt),
+            "updated_at" => Ok(GameSort::UpdatedAt),
+            "move_count" => Ok(GameSort::MoveCount),
+            "status" => Ok(GameSort::Status),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Returns a copy of the game as it's returned to clients, with the amount of connected event
+/// streams filled in
+///
+/// # Arguments
+///
+/// * 'game' - The stored game
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+fn spectated(game: &Game, watchers: &Watchers) -> Game {
+    let mut game = game.clone();
+    game.set_spectators(watchers.spectators(game.get_id().as_deref().unwrap_or_default()));
+    game
+}
+
+/// Gets a list of all games and returns them as as an array
+///
+/// Archived games are left out. Finished games are left out as well if configured to hide them,
+/// unless include_finished is set.
+///
+/// The games are sorted by id unless another field is chosen, games with equal values are ordered
+/// by id so the order is stable.
+///
+/// # Arguments
+///
+/// * 'include_finished' - Optional query parameter, whether finished games are listed even if configured to hide them
+///
+/// * 'sort' - Optional query parameter, field to sort by: id, created_at, updated_at, move_count or status
+///
+/// * 'order' - Optional query parameter, asc or desc, defaults to asc
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+#[get("/games?<include_finished>&<sort>&<order>")]
+fn all_games(
+    include_finished: Option<bool>,
+    sort: Option<&str>,
+    order: Option<&str>,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+    watchers: &State<Watchers>,
+) -> Result<APIResponse<Vec<Game>>, APIResponse<ErrorMessage>> {
+    let sort = match sort {
+        None => GameSort::Id,
+        Some(field) => GameSort::try_from(field).map_err(|_| {
+            ErrorMessage::response(
+                Status::BadRequest,
+                format!("Unable to list games: unknown sort field {}", field),
+            )
+        })?,
+    };
+    let descending = match order {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(order) => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                format!(
+                    "Unable to list games: order must be asc or desc, got {}",
+                    order
+                ),
+            ))
+        }
+    };
+
+    let hide_finished = config.hide_finished_games && !include_finished.unwrap_or(false);
+    let lock = game_list.inner(); // Getting state
+    let guard = lock.list.lock().unwrap();
+    let mut all_games = guard
+        .values()
+        .filter(|game| !game.is_archived())
+        .filter(|game| !hide_finished || game.get_status().as_deref() == Some("RUNNING"))
+        .map(|game| spectated(game, watchers))
+        .collect::<Vec<Game>>();
+
+    all_games.sort_by(|a, b| {
+        let ordering = match sort {
+            GameSort::Id => std::cmp::Ordering::Equal,
+            GameSort::CreatedAt => a.get_created_at().cmp(&b.get_created_at()),
+            GameSort::UpdatedAt => a.get_updated_at().cmp(&b.get_updated_at()),
+            GameSort::MoveCount => a.move_count().cmp(&b.move_count()),
+            GameSort::Status => a.get_status().cmp(b.get_status()),
+        }
+        .then_with(|| a.get_id().cmp(b.get_id()));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    Ok(APIResponse {
+        json: Json(all_games),
+        status: Status::Ok,
+    })
+}
+
+/// Gets the IDs of all games and returns them as an array
+///
+/// Much lighter than listing the full games when building an index. Archived games are left out.
+///
+/// # Arguments
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/ids")]
+fn game_ids(game_list: &State<GameList>) -> APIResponse<Vec<String>> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let ids = guard
+        .iter()
+        .filter(|(_, game)| !game.is_archived())
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<String>>();
+
+    APIResponse {
+        json: Json(ids),
+        status: Status::Ok,
+    }
+}
+
+/// Finds the games whose current board is the submitted position, e.g. to spot duplicate positions.
+///
+/// The board is sent as plain text and read the same way as a board in any other request.
+/// Archived games are left out.
+///
+/// # Arguments
+///
+/// * 'board' - POST request payload, the board to search for
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/search", data = "<board>")]
+fn search_games(
+    board: String,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+) -> Result<APIResponse<Vec<String>>, APIResponse<ErrorMessage>> {
+    let board = if config.strict_boards {
+        &board
+    } else {
+        trim_board(&board)
+    };
+    let board = internal_marks(board, config.marks);
+    let board = match formats::parse(&board, BoardFormat::Flat, &config.empty_aliases) {
+        Ok(board) => board,
+        Err(e) => {
+            let status = create_error_status(&e);
+            return Err(ErrorMessage::response(
+                status,
+                format!("Unable to search games: {}", e.reason()),
+            ));
+        }
+    };
+
+    let guard = game_list.inner().list.lock().unwrap();
+    let mut ids = guard
+        .iter()
+        .filter(|(_, game)| !game.is_archived() && *game.get_board() == board)
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<String>>();
+    ids.sort();
+
+    Ok(APIResponse {
+        json: Json(ids),
+        status: Status::Ok,
+    })
+}
+
+/// IDs of games grouped by status
+#[derive(Serialize, Default)]
+struct GroupedGames {
+    #[serde(rename = "RUNNING")]
+    running: Vec<String>,
+    #[serde(rename = "X_WON")]
+    x_won: Vec<String>,
+    #[serde(rename = "O_WON")]
+    o_won: Vec<String>,
+    #[serde(rename = "DRAW")]
+    draw: Vec<String>,
+}
+
+/// Gets the IDs of all games grouped by status for results dashboards
+///
+/// Archived games are left out. IDs are sorted within each group.
+///
+/// # Arguments
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/grouped")]
+fn grouped_games(game_list: &State<GameList>) -> APIResponse<GroupedGames> {
+    let mut grouped = GroupedGames::default();
+    for (id, game) in game_list.inner().list.lock().unwrap().iter() {
+        if game.is_archived() {
+            continue;
+        }
+        let group = match game.get_status().as_deref() {
+            Some("X_WON") => &mut grouped.x_won,
+            Some("O_WON") => &mut grouped.o_won,
+            Some("DRAW") => &mut grouped.draw,
+            _ => &mut grouped.running,
+        };
+        group.push(id.clone());
+    }
+    for group in [
+        &mut grouped.running,
+        &mut grouped.x_won,
+        &mut grouped.o_won,
+        &mut grouped.draw,
+    ] {
+        group.sort();
+    }
+
+    APIResponse {
+        json: Json(grouped),
+        status: Status::Ok,
+    }
+}
+
+/// Win/loss/draw record of an owner
+#[derive(Serialize, Default)]
+struct OwnerStats {
+    /// Finished games won by the player
+    wins: u32,
+    /// Finished games won by the computer
+    losses: u32,
+    /// Finished games ending in a draw
+    draws: u32,
+    /// Games still running
+    running: u32,
+}
+
+/// Returns the win/loss/draw record across all games owned by the owner token.
+///
+/// Wins and losses are from the player's perspective, using the player's sign choice of each game.
+///
+/// # Arguments
+///
+/// * 'token' - Parsed from the query, owner token returned when creating games
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'tokens' - Maintains a map of the read-only and owner tokens of each game in a mutex to handle async requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/stats?<token>")]
+fn owner_stats(
+    token: String,
+    game_list: &State<GameList>,
+    tokens: &State<TokenList>,
+) -> APIResponse<OwnerStats> {
+    let games = game_list.inner().list.lock().unwrap();
+    let mut stats = OwnerStats::default();
+
+    for id in tokens.owned_games(&token) {
+        let game = match games.get(&id) {
+            Some(game) => game,
+            None => continue, // Game has been deleted
+        };
+
+        match (game.get_status().as_deref(), game.player_sign()) {
+            (Some("X_WON"), Sign::X) | (Some("O_WON"), Sign::O) => stats.wins += 1,
+            (Some("X_WON"), _) | (Some("O_WON"), _) => stats.losses += 1,
+            (Some("DRAW"), _) => stats.draws += 1,
+            _ => stats.running += 1,
+        }
+    }
+
+    APIResponse {
+        json: Json(stats),
+        status: Status::Ok,
+    }
+}
+
+/// Gets the games of the client's owner token and returns them as an array sorted by ID
+///
+/// The owner token is read from the X-Owner-Token header or the session cookie set when creating a game,
+/// so browsers list their own games without handling tokens. Archived games are left out, and clients
+/// without an owner token get an empty array.
+///
+/// # Arguments
+///
+/// * 'owner' - Owner token supplied by the client
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'tokens' - Maintains a map of the read-only and owner tokens of each game in a mutex to handle async requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/mine")]
+fn my_games(
+    owner: OwnerToken,
+    game_list: &State<GameList>,
+    tokens: &State<TokenList>,
+    watchers: &State<Watchers>,
+) -> APIResponse<Vec<Game>> {
+    let mut ids = match &owner.0 {
+        Some(token) => tokens.owned_games(token),
+        None => vec![],
+    };
+    ids.sort();
+
+    let games = game_list.inner().list.lock().unwrap();
+    let mine = ids
+        .iter()
+        .filter_map(|id| games.get(id))
+        .filter(|game| !game.is_archived())
+        .map(|game| spectated(game, watchers))
+        .collect::<Vec<Game>>();
+
+    APIResponse {
+        json: Json(mine),
+        status: Status::Ok,
+    }
+}
+
+/// Returns the current game object based on its ID which is parsed from the URL.
+///
+/// Recently deleted games answer with 410 Gone instead of 404 Not Found.
+/// Archived games are only returned when asked for with include_archived.
+///
+/// When configured to auto-create games, an unknown ID written like the UUIDs the API generates gets a
+/// new game with that ID, created from the default board, unless it was recently deleted. Creating it
+/// fails with 503 Service Unavailable once the maximum number of games is reached.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'include_archived' - Optional query parameter, whether an archived game is returned
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'tombstones' - Keeps a bounded record of recently deleted game IDs
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>?<include_archived>")]
+fn game_board(
+    id: String,
+    include_archived: Option<bool>,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    watchers: &State<Watchers>,
+    tombstones: &State<Tombstones>,
+) -> Result<WithHeaders<APIResponse<Game>>, Status> {
+    let lock = game_list.inner(); // Getting state
+    let current_game;
+    if config.auto_create_games
+        && !tombstones.is_buried(&id)
+        && Uuid::parse_str(&id).is_ok_and(|uuid| uuid.to_string() == id)
+    {
+        // Checking and inserting under the same lock, so concurrent first requests create one game
+        let mut guard = lock.list.lock().unwrap();
+        if !guard.contains_key(&*id) {
+            if guard.len() >= config.max_games {
+                return Err(Status::ServiceUnavailable);
+            }
+            let new_game = NewGame::with_board(config.default_board.clone());
+            let mut game = match Game::new(&new_game, player_signs, config, strategies) {
+                Ok(game) => game,
+                Err(e) => {
+                    error!("Unable to auto-create game from the default board: {}", e);
+                    return Err(Status::InternalServerError);
+                }
+            };
+            game.set_id(id.clone(), player_signs);
+            guard.insert(id.clone(), game);
+        }
+    }
+    if lock.list.lock().unwrap().contains_key(&*id) {
+        // If id exists, get the game
+        let guard = lock.list.lock().unwrap();
+        let map_entry = guard.get(&*id);
+        match map_entry {
+            Some(game) if game.is_archived() && !include_archived.unwrap_or(false) => {
+                return Err(Status::NotFound);
+            }
+            Some(game) => current_game = game,
+            _ => {
+                return Err(Status::InternalServerError); // Should be unreachable;
+            }
+        }
+        return Ok(WithHeaders {
+            inner: APIResponse {
+                json: Json(spectated(current_game, watchers)),
+                status: Status::Ok,
+            },
+            headers: vec![Header::new("ETag", current_game.get_etag())],
+        });
+    }
+    if tombstones.is_buried(&id) {
+        return Err(Status::Gone);
+    }
+    Err(Status::NotFound)
+}
+
+/// Returns the board of the game as a plain text 3x3 grid for CLI clients and debugging.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/ascii")]
+fn game_ascii(
+    id: String,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+) -> Result<String, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    match guard.get(&*id) {
+        Some(game) => Ok(custom_marks(&game.to_ascii(), config.marks)),
+        None => Err(Status::NotFound),
+    }
+}
+
+/// Returns the moves of the game as a plain text transcript of sign and cell index pairs, e.g. "X:4 O:0".
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/notation")]
+fn game_notation(
+    id: String,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+) -> Result<String, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    match guard.get(&*id) {
+        Some(game) => Ok(custom_marks(&game.to_notation(), config.marks)),
+        None => Err(Status::NotFound),
+    }
+}
+
+/// Returns the state of the game as a single short line of board, status and side to move separated
+/// by '|', e.g. "X-O-----O|RUNNING|X", for embedded clients syncing with minimal bandwidth.
+///
+/// The side to move is '-' once the game is over. See CompactState.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/compact")]
+fn game_compact(
+    id: String,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+) -> Result<String, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let game = match guard.get(&*id) {
+        Some(game) => game,
+        None => return Err(Status::NotFound),
+    };
+    let status = game
+        .get_status()
+        .as_deref()
+        .and_then(|status| GameStatus::try_from(status).ok())
+        .ok_or(Status::InternalServerError)?;
+
+    let state = CompactState {
+        board: game.get_board(),
+        status,
+        to_move: game.to_move(game.player_sign()),
+    };
+    Ok(state.with_marks(config.marks))
+}
+
+/// The board of a game at a point in its history
+#[derive(Serialize)]
+struct Position {
+    /// Amount of moves made
+    ply: usize,
+    /// Representation of the board after the moves
+    board: String,
+}
+
+/// Returns the board of the game as it was after the given amount of moves, for scrubbing through the game.
+///
+/// Returns 400 Bad Request if fewer moves have been made.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'ply' - Parsed from the URL, amount of moves made
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/at/<ply>")]
+fn game_at(
+    id: String,
+    ply: usize,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Position>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let board = match guard.get(&*id) {
+        Some(game) => game.board_at(ply).ok_or(Status::BadRequest)?,
+        None => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(Position { ply, board }),
+        status: Status::Ok,
+    })
+}
+
+/// Returns the board of the game as an SVG image for embedding in web pages.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/svg")]
+fn game_svg(id: String, game_list: &State<GameList>) -> Result<(ContentType, String), Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    match guard.get(&*id) {
+        Some(game) => Ok((ContentType::SVG, game.to_svg())),
+        None => Err(Status::NotFound),
+    }
+}
+
+/// Returns a game to a spectator holding the game's read-only token.
+///
+/// Allows a game to be shared publicly without sharing the ability to make moves.
+/// A wrong token is refused before the game is looked up, archived games are not returned.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'token' - Parsed from the query, read-only token issued when the game was created
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'tokens' - Maintains a map of the read-only tokens of each game in a mutex to handle async requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/view?<token>")]
+fn view_game(
+    id: String,
+    token: String,
+    game_list: &State<GameList>,
+    tokens: &State<TokenList>,
+    watchers: &State<Watchers>,
+) -> Result<APIResponse<Game>, Status> {
+    // Checked first so the response doesn't reveal whether a game exists to holders of no token
+    if !tokens.can_read(&id, &token) {
+        return Err(Status::Forbidden);
+    }
+
+    let guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get(&*id) {
+        Some(game) if !game.is_archived() => game,
+        _ => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(spectated(current_game, watchers)),
+        status: Status::Ok,
+    })
+}
+
+/// Canonical form of a board under the symmetries of the square
+#[derive(Serialize)]
+struct CanonicalBoard {
+    /// Lexicographically smallest symmetric form of the board
+    canonical: String,
+    /// Name of the symmetry that transforms the board into its canonical form
+    transform: &'static str,
+}
+
+/// Returns the canonical form of the game's board under the 8 rotations and reflections of the square.
+///
+/// Equivalent positions share a canonical form, which is useful for deduplicating positions.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/canonical")]
+fn game_canonical(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<CanonicalBoard>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get(&*id) {
+        Some(game) => game,
+        None => return Err(Status::NotFound),
+    };
+
+    let (canonical, transform) = canonical(&current_game.get_board());
+    Ok(APIResponse {
+        json: Json(CanonicalBoard {
+            canonical,
+            transform,
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Occurrences of the current position of a game
+#[derive(Serialize)]
+struct Repetitions {
+    /// Canonical form of the current position, see CanonicalBoard
+    canonical: String,
+    /// Amount of times the position or a position symmetric to it has occurred in the game, including now
+    count: u32,
+}
+
+/// Counts how many times the current position of a game has occurred, with symmetric positions counted
+/// as the same position.
+///
+/// Signs are never removed by moves, so positions only recur when moves are taken back and made again,
+/// e.g. when the computer's reply is undone and it replies in a symmetric cell. Games loaded from an
+/// export only count the positions in their history.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/repetitions")]
+fn game_repetitions(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Repetitions>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get(&*id) {
+        Some(game) => game,
+        None => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(Repetitions {
+            canonical: canonical(&current_game.get_board()).0,
+            count: current_game.repetitions(),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// The board as a pair of bitboards
+#[derive(Serialize)]
+struct Bitboards {
+    /// Cells of X, bit i standing for the cell at index i
+    x: u16,
+    /// Cells of O, bit i standing for the cell at index i
+    o: u16,
+}
+
+/// Returns the board of the game as two 9-bit integers, the cells of X and the cells of O.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/bitboard")]
+fn game_bitboard(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Bitboards>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let (x, o) = match guard.get(&*id) {
+        Some(game) => board_to_bitboards(&game.get_board()),
+        None => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(Bitboards { x, o }),
+        status: Status::Ok,
+    })
+}
+
+/// Cells each side could win the game in with their next move
+#[derive(Serialize)]
+struct Threats {
+    /// Winning cells for X
+    x: Vec<usize>,
+    /// Winning cells for O
+    o: Vec<usize>,
+}
+
+/// Returns the cells each side could complete a line in with their next move.
+///
+/// Both sides are listed regardless of whose turn it is, a finished game has no threats.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/threats")]
+fn game_threats(id: String, game_list: &State<GameList>) -> Result<APIResponse<Threats>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let board = match guard.get(&*id) {
+        Some(game) => game.get_board(),
+        None => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(Threats {
+            x: winning_cells(&board, Sign::X),
+            o: winning_cells(&board, Sign::O),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Cells each side could create a fork in with their next move
+#[derive(Serialize)]
+struct Forks {
+    /// Forking cells for X
+    x: Vec<usize>,
+    /// Forking cells for O
+    o: Vec<usize>,
+}
+
+/// Returns the cells where each side could create a fork, two winning threats at once, with their next move.
+///
+/// Both sides are listed regardless of whose turn it is, a finished game has no forks.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/forks")]
+fn game_forks(id: String, game_list: &State<GameList>) -> Result<APIResponse<Forks>, Status> {
+    let guard = game_list.inner().list.lock().unwrap();
+    let board = match guard.get(&*id) {
+        Some(game) => game.get_board(),
+        None => return Err(Status::NotFound),
+    };
+
+    Ok(APIResponse {
+        json: Json(Forks {
+            x: fork_cells(&board, Sign::X),
+            o: fork_cells(&board, Sign::O),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Reads the board of a running game along with the side to move, for analysing the position
+///
+/// The side to move is taken from the game, as the player is not always to move: the computer is after
+/// its reply was undone, and both sides take turns in analysis games.
+///
+/// Fails with 404 Not Found if the game doesn't exist and 400 Bad Request if it's over
+///
+/// # Arguments
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'id' - ID of the game
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+fn running_position(game_list: &GameList, id: &str) -> Result<(String, Sign), Status> {
+    match game_list.list.lock().unwrap().get(id) {
+        Some(game) => match game.to_move(game.player_sign()) {
+            Some(to_move) => Ok((game.get_board(), to_move)),
+            None => Err(Status::BadRequest), // Finished games have no position to analyse
+        },
+        None => Err(Status::NotFound),
+    }
+}
+
+/// Runs a minimax search off the async workers, a full search of an early position takes a moment
+///
+/// Fails with 500 Internal Server Error if the search panics
+///
+/// # Arguments
+///
+/// * 'search' - The search
+async fn solve<T: Send + 'static>(
+    search: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, Status> {
+    rocket::tokio::task::spawn_blocking(search)
+        .await
+        .map_err(|_| Status::InternalServerError)
+}
+
+/// Minimax evaluation of a position
+#[derive(Serialize)]
+struct Evaluation {
+    /// Sign of the side to move
+    to_move: Sign,
+    /// Score from the perspective of the side to move, positive when it can force a win
+    score: i32,
+    /// Index of the best cell for the side to move
+    best_move: usize,
+}
+
+/// Evaluates the current position of a running game with minimax.
+///
+/// The score is from the perspective of the side to move, 10 for a win, 0 for a draw and -10 for
+/// a loss, adjusted towards 0 by the amount of moves it takes to reach the result.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/eval")]
+async fn game_eval(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Evaluation>, Status> {
+    let (board, to_move) = running_position(game_list, &id)?;
+
+    match solve(move || evaluate(&board, to_move)).await? {
+        Some((best_move, score)) => Ok(APIResponse {
+            json: Json(Evaluation {
+                to_move,
+                score,
+                best_move,
+            }),
+            status: Status::Ok,
+        }),
+        None => Err(Status::BadRequest),
+    }
+}
+
+/// A legal move along with its minimax evaluation
+#[derive(Serialize)]
+struct RankedMove {
+    /// Index of the cell
+    index: usize,
+    /// Score of the move from the perspective of the side to move, see Evaluation
+    score: i32,
+}
+
+/// Legal moves of a position ranked by their minimax evaluation
+#[derive(Serialize)]
+struct RankedMoves {
+    /// Sign of the side to move
+    to_move: Sign,
+    /// Legal moves, best first
+    moves: Vec<RankedMove>,
+}
+
+/// Ranks the legal moves in the current position of a running game with minimax, best first.
+///
+/// Scores are as in game_eval, moves with equal scores are ordered by index.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/ranked-moves")]
+async fn game_ranked_moves(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<RankedMoves>, Status> {
+    let (board, to_move) = running_position(game_list, &id)?;
+
+    let moves = solve(move || ranked_moves(&board, to_move))
+        .await?
+        .into_iter()
+        .map(|(index, score)| RankedMove { index, score })
+        .collect();
+    Ok(APIResponse {
+        json: Json(RankedMoves { to_move, moves }),
+        status: Status::Ok,
+    })
+}
+
+/// Result of perfect play from a position
+#[derive(Serialize)]
+struct Outcome {
+    /// Sign of the side to move
+    to_move: Sign,
+    /// Status the game ends in with perfect play by both sides, X_WON, O_WON or DRAW
+    outcome: &'static str,
+}
+
+/// Solves the current position of a running game with minimax.
+///
+/// Returns how the game ends when both sides play perfectly from here on.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/outcome")]
+async fn game_outcome(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Outcome>, Status> {
+    let (board, to_move) = running_position(game_list, &id)?;
+
+    let score = match solve(move || evaluate(&board, to_move)).await? {
+        Some((_, score)) => score,
+        None => return Err(Status::BadRequest),
+    };
+    let winner = match score.signum() {
+        1 => Some(to_move),
+        -1 => Some(to_move.opponent()),
+        _ => None,
+    };
+    let outcome = match winner {
+        Some(Sign::X) => GameStatus::XWon,
+        Some(Sign::O) => GameStatus::OWon,
+        None => GameStatus::DRAW,
+    };
+
+    Ok(APIResponse {
+        json: Json(Outcome {
+            to_move,
+            outcome: outcome.as_str(),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Distance to the end of a game with perfect play
+#[derive(Serialize)]
+struct DistanceToEnd {
+    /// Sign of the side to move
+    to_move: Sign,
+    /// Amount of plies until the game ends with perfect play by both sides
+    plies: u32,
+}
+
+/// Counts the plies left in a running game when both sides play perfectly, the distance to the forced
+/// win, loss or draw.
+///
+/// The side that can force a win takes the quickest one and the other side delays it as long as it can,
+/// as in game_eval. A drawn game lasts until the board is full.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/dtm")]
+async fn game_distance_to_end(
+    id: String,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<DistanceToEnd>, Status> {
+    let (board, to_move) = running_position(game_list, &id)?;
+
+    match solve(move || distance_to_end(&board, to_move)).await? {
+        Some(plies) => Ok(APIResponse {
+            json: Json(DistanceToEnd { to_move, plies }),
+            status: Status::Ok,
+        }),
+        None => Err(Status::BadRequest),
+    }
+}
+
+/// Amount of distinct positions reachable from the current position of a game
+#[derive(Serialize)]
+struct Reachable {
+    /// Maximum amount of plies played from the current position
+    depth: u32,
+    /// Whether symmetric positions were counted as one
+    symmetric: bool,
+    /// Amount of distinct positions, the current position not included
+    positions: usize,
+}
+
+/// Counts the distinct positions reachable within depth plies from the current position of a game,
+/// for teaching combinatorics.
+///
+/// The depth defaults to 1 and may be at most 9, the most plies a game can last. Positions that are
+/// symmetric to each other are counted once when symmetric is set. Finished games reach no positions.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'depth' - Parsed from the URL, maximum amount of plies played from the current position
+///
+/// * 'symmetric' - Parsed from the URL, whether symmetric positions count as one, defaults to false
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/reachable?<depth>&<symmetric>")]
+async fn game_reachable(
+    id: String,
+    depth: Option<u32>,
+    symmetric: Option<bool>,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<Reachable>, APIResponse<ErrorMessage>> {
+    let depth = depth.unwrap_or(1);
+    let symmetric = symmetric.unwrap_or(false);
+    if depth > 9 {
+        return Err(ErrorMessage::response(
+            Status::BadRequest,
+            "Unable to count positions: depth must be at most 9",
+        ));
+    }
+    let (board, to_move) = match game_list.inner().list.lock().unwrap().get(&*id) {
+        // Finished games reach no positions whichever side is taken to move
+        Some(game) => (
+            game.get_board(),
+            game.to_move(game.player_sign()).unwrap_or(Sign::X),
+        ),
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+
+    // Counting every continuation of an early position takes a moment, keep it off the async workers
+    match rocket::tokio::task::spawn_blocking(move || {
+        reachable_positions(&board, to_move, depth, symmetric)
+    })
+    .await
+    {
+        Ok(positions) => Ok(APIResponse {
+            json: Json(Reachable {
+                depth,
+                symmetric,
+                positions,
+            }),
+            status: Status::Ok,
+        }),
+        Err(_) => Err(ErrorMessage::response(
+            Status::InternalServerError,
+            "Unable to count positions",
+        )),
+    }
+}
+
+/// Returns the minimax game tree from the current position of a running game, for educational tooling.
+///
+/// Every node carries its board and score, see game_eval. The depth defaults to 1 and may be at most
+/// MAX_TREE_DEPTH.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'depth' - Parsed from the URL, amount of moves to expand below the current position
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/tree?<depth>")]
+async fn game_tree(
+    id: String,
+    depth: Option<u32>,
+    game_list: &State<GameList>,
+) -> Result<APIResponse<TreeNode>, APIResponse<ErrorMessage>> {
+    let depth = depth.unwrap_or(1);
+    if depth > MAX_TREE_DEPTH {
+        return Err(ErrorMessage::response(
+            Status::BadRequest,
+            format!(
+                "Unable to build tree: depth must be at most {}",
+                MAX_TREE_DEPTH
+            ),
+        ));
+    }
+    let (board, to_move) = running_position(game_list, &id).map_err(|status| {
+        let reason = match status.code {
+            404 => "Game not found",
+            _ => "Unable to build tree: game is over",
+        };
+        ErrorMessage::response(status, reason)
+    })?;
+
+    // Deep trees take a while to evaluate, keep them off the async workers
+    match rocket::tokio::task::spawn_blocking(move || tree(&board, to_move, depth)).await {
+        Ok(root) => Ok(APIResponse {
+            json: Json(root),
+            status: Status::Ok,
+        }),
+        Err(_) => Err(ErrorMessage::response(
+            Status::InternalServerError,
+            "Unable to build tree",
+        )),
+    }
+}
+
+/// Winning line of a won game
+#[derive(Serialize)]
+struct WinType {
+    /// Sign that completed the line
+    sign: Sign,
+    /// Whether the line is a row, column or diagonal
+    kind: LineKind,
+    /// Index of the line among lines of its kind
+    index: usize,
+    /// Indexes of the cells that make up the line
+    cells: [usize; 3],
+}
+
+/// Response of a win type request
+#[derive(Responder)]
+enum WinTypeResponse {
+    /// The game was won with a line
+    Won(APIResponse<WinType>),
+    /// The game is running, drawn or was forfeited without a line
+    #[response(status = 204)]
+    NoLine(()),
+}
+
+/// Classifies the line a finished game was won with, for analytics.
+///
+/// Rows and columns are numbered from the top and the left, diagonal 0 runs from the top left corner
+/// and diagonal 1 from the top right corner. Returns 204 No Content when there is no winning line.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/win-type")]
+fn game_win_type(id: String, game_list: &State<GameList>) -> Result<WinTypeResponse, Status> {
+    let board = match game_list.inner().list.lock().unwrap().get(&*id) {
+        Some(game) => game.get_board(),
+        None => return Err(Status::NotFound),
+    };
+
+    let line = match winning_line(&board) {
+        Some(line) => line,
+        None => return Ok(WinTypeResponse::NoLine(())),
+    };
+    let cells = LINES[line];
+    let sign = match Sign::try_from(board.as_bytes()[cells[0]] as char) {
+        Ok(sign) => sign,
+        Err(_) => return Err(Status::InternalServerError), // Completed lines are made of signs
+    };
+    let (kind, index) = classify_line(line);
+
+    Ok(WinTypeResponse::Won(APIResponse {
+        json: Json(WinType {
+            sign,
+            kind,
+            index,
+            cells,
+        }),
+        status: Status::Ok,
+    }))
+}
+
+/// Response of a long-polling request
+// Rocket can't respond with a boxed game, and the response is short lived anyway
+#[allow(clippy::large_enum_variant)]
+#[derive(Responder)]
+enum WaitResponse {
+    /// The game changed, or differs from the version the client saw
+    Changed(APIResponse<Game>),
+    /// Nothing changed before the timeout
+    #[response(status = 304)]
+    Unchanged(()),
+}
+
+/// Waits for the game to change and returns it, as a middle ground between polling and a push connection.
+///
+/// Returns immediately if the game's version differs from the version the client last saw.
+/// Otherwise the request is held open until a move is made or the configured timeout elapses,
+/// in which case 304 Not Modified is returned.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'since' - Parsed from the query, version of the game last seen by the client.
+/// Waits for the next change if missing.
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/wait?<since>")]
+async fn wait_for_change(
+    id: String,
+    since: Option<u32>,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    config: &State<Config>,
+) -> Result<WaitResponse, Status> {
+    let channel;
+    let notified;
+    {
+        let guard = game_list.inner().list.lock().unwrap();
+        let current_game = match guard.get(&*id) {
+            Some(game) => game,
+            None => return Err(Status::NotFound),
+        };
+        // Subscribing only once the game is known to exist, so unknown IDs don't leave notifiers behind.
+        // Still under the lock and before checking the version, so a change in between isn't missed
+        channel = watchers.subscribe(&id);
+        notified = channel.notify.notified();
+        if let Some(version) = since {
+            if version != current_game.get_version() {
+                return Ok(WaitResponse::Changed(APIResponse {
+                    json: Json(spectated(current_game, watchers)),
+                    status: Status::Ok,
+                }));
+            }
+        }
+    }
+
+    if timeout(Duration::from_secs(config.long_poll_timeout), notified)
+        .await
+        .is_err()
+    {
+        return Ok(WaitResponse::Unchanged(()));
+    }
+
+    let guard = game_list.inner().list.lock().unwrap();
+    match guard.get(&*id) {
+        Some(game) => Ok(WaitResponse::Changed(APIResponse {
+            json: Json(spectated(game, watchers)),
+            status: Status::Ok,
+        })),
+        None => Err(Status::NotFound), // Game was deleted while waiting
+    }
+}
+
+/// Streams the game as server-sent events, sending the game once on connect and again every time it changes.
+///
+/// Connected streams are counted as spectators of the game, the count is included in the events and
+/// in GET /games/<id>. A disconnected stream stops being counted at the next heartbeat.
+/// The stream ends when the game is deleted or the server shuts down.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'shutdown' - Resolves when the server shuts down
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/games/<id>/events")]
+fn game_events(
+    id: String,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    config: &State<Config>,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![], Status> {
+    if !game_list.inner().list.lock().unwrap().contains_key(&*id) {
+        return Err(Status::NotFound);
+    }
+    let games = game_list.inner().clone();
+    let spectator = watchers.spectate(&id);
+    let marks = config.marks;
+
+    Ok(EventStream! {
+        let channel = spectator.channel.clone();
+        loop {
+            // Waiting on the notifier before reading the game so a change in between isn't missed
+            let notified = channel.notify.notified();
+            let mut game = match games.list.lock().unwrap().get(&*id) {
+                Some(game) => game.clone(),
+                None => break, // Game was deleted
+            };
+            game.set_spectators(channel.spectators.load(Ordering::SeqCst));
+            let mut value = json::to_value(&game).unwrap_or_default();
+            localize_marks(&mut value, marks);
+            yield Event::json(&value);
+
+            select! {
+                _ = notified => continue,
+                _ = &mut shutdown => break,
+            }
+        }
+        drop(spectator);
+    }
+    // Frequent heartbeats let disconnected spectators be noticed and uncounted quickly
+    .heartbeat(Duration::from_secs(5)))
+}
+
+/// Waits for the configured artificial computer move delay, if any
+///
+/// # Arguments
+///
+/// * 'config' - Runtime configuration of the API
+async fn computer_move_delay(config: &Config) {
+    if config.computer_move_delay > 0 {
+        sleep(Duration::from_millis(config.computer_move_delay)).await;
+    }
+}
+
+/// Returns the status a move rejected with the error is answered with
+///
+/// Boards that change more than one cell get 409 Conflict so clients can tell them apart from
+/// other illegal moves, which get 422 Unprocessable Entity as the payload itself was well formed.
+///
+/// # Arguments
+///
+/// * 'error' - Reason the move was rejected
+fn move_error_status(error: &MoveError) -> Status {
+    match error {
+        MoveError::MultipleCellsChanged => Status::Conflict,
+        _ => Status::UnprocessableEntity,
+    }
+}
+
+/// Returns the status a board or game rejected with the error is answered with
+///
+/// Malformed boards and options get 400 Bad Request. Well formed boards that break the rules of the
+/// game, like an impossible turn count, get 409 Conflict.
+///
+/// # Arguments
+///
+/// * 'error' - Reason the board or game was rejected
+fn create_error_status(error: &CreateError) -> Status {
+    match error {
+        CreateError::InvalidLength(_)
+        | CreateError::InvalidCharacter(_)
+        | CreateError::UnknownStrategy(_)
+        | CreateError::InvalidNotation(_)
+        | CreateError::InvalidSign(_)
+        | CreateError::InvalidBias(_)
+        | CreateError::InvalidBitboards(_) => Status::BadRequest,
+        CreateError::IllegalTurnBalance
+        | CreateError::IllegalPosition
+        | CreateError::AlreadyWon => Status::Conflict,
+    }
+}
+
+/// Returns whether the client may move in the game
+///
+/// Any client may move in games without move tokens. In PvP games the client must send the move token
+/// of the side to move, X and O strictly alternating starting with X.
+///
+/// # Arguments
+///
+/// * 'move_tokens' - Move tokens of X and O of the game, None if it's not a PvP game
+///
+/// * 'board' - Representation of the stored board of the game
+///
+/// * 'token' - Move token supplied by the client, if any
+fn may_move(move_tokens: &Option<(String, String)>, board: &str, token: &MoveToken) -> bool {
+    let (x_token, o_token) = match move_tokens {
+        Some(tokens) => tokens,
+        None => return true,
+    };
+    let expected = match side_to_move(board, Sign::X) {
+        Sign::X => x_token,
+        Sign::O => o_token,
+    };
+    token.0.as_deref() == Some(expected.as_str())
+}
+
+/// Handles the put request to make a new move to a specified game
+///
+/// Gets the active game by id parsed from the URL and tries to make the user defined moved
+/// which is the payload in the PUT request.
+///
+/// Returns the updated game board with the computer's response move updated to the board
+///
+/// If the request has an If-Match header that doesn't match the ETag of the stored game, the game has
+/// changed since the client last saw it and the move is rejected with 412 Precondition Failed.
+/// The new ETag is returned in the ETag header, and the cell the computer replied in in the
+/// X-Computer-Move header when it replied.
+///
+/// Moves in PvP games must carry the move token of the side to move in the X-Move-Token header, otherwise
+/// they are rejected with 403 Forbidden.
+///
+/// An ID in the payload must match the ID in the URL, otherwise the move is rejected with 400 Bad Request.
+/// Payloads that aren't a game are rejected with 400 Bad Request as well, while well formed games with an
+/// illegal move are rejected with 422 Unprocessable Entity, see move_error_status.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'game' - Payload in the PUT request, contains to game object with an updated board. (Player move)
+///   Or the reason it couldn't be read as a game
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'if_match' - ETag the client expects the game to have, if any
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// * 'move_token' - Move token supplied by the client, required in PvP games
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[put("/games/<id>", format = "json", data = "<game>")]
+async fn put_player_move(
+    id: String,
+    game_list: &State<GameList>,
+    game: Result<Json<Game>, json::Error<'_>>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    watchers: &State<Watchers>,
+    if_match: IfMatch,
+    tokens: &State<TokenList>,
+    move_token: MoveToken,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<APIResponse<Game>>, Status> {
+    // Malformed JSON and JSON that isn't a game are both bad requests
+    let submitted_new_game_state = game.map_err(|_| Status::BadRequest)?;
+
+    // Rejecting payloads meant for another game
+    if submitted_new_game_state
+        .get_id()
+        .as_ref()
+        .is_some_and(|body_id| *body_id != id)
+    {
+        return Err(Status::BadRequest);
+    }
+
+    let new_board = submitted_new_game_state.get_board(); // generate new board based on moves TEMP
+
+    // Letting the computer "think" before it replies, requests it won't reply to aren't held up
+    let replies = game_list
+        .inner()
+        .list
+        .lock()
+        .unwrap()
+        .get(&*id)
+        .is_some_and(|game| {
+            if_match.matches(&game.get_etag()) && game.replies_to(&new_board, config)
+        });
+    if replies {
+        computer_move_delay(config).await;
+    }
+    let game_id = id.clone();
+    let games = game_list.inner().clone();
+    let (settings, registry) = (config.inner().clone(), strategies.inner().clone());
+    let move_tokens = tokens.move_tokens(&id);
+
+    // The move is made on a copy that replaces the stored game only if it's done in time
+    let (outcome, game) = deadline::run(config.request_timeout, move |deadline| {
+        let mut guard = games.list.lock().unwrap();
+        let mut current_game = match guard.get(&*game_id) {
+            Some(game) => game.clone(),
+            None => return Err(Status::NotFound),
+        };
+        // Rejecting moves made against an outdated version of the game
+        if !if_match.matches(&current_game.get_etag()) {
+            return Err(Status::PreconditionFailed);
+        }
+        // Checked against the locked game, so the side to move can't change in between
+        if !may_move(&move_tokens, &current_game.get_board(), &move_token) {
+            return Err(Status::Forbidden);
+        }
+        let outcome = current_game
+            .make_move(new_board, &settings, &registry)
+            .map_err(|e| move_error_status(&e))?;
+        if deadline.claim() {
+            let mut stored_game = current_game.clone();
+            stored_game.clear_computer_move_kind();
+            guard.insert(game_id, stored_game);
+        }
+        Ok((outcome, current_game))
+    })
+    .await??;
+
+    if outcome.game_over {
+        transcript::record(&game, config);
+    }
+    watchers.notify(&id);
+    let mut headers = vec![Header::new("ETag", game.get_etag())];
+    if let Some(reply) = &outcome.computer_move {
+        headers.push(Header::new("X-Computer-Move", reply.index.to_string()));
+    }
+    Ok(WithHeaders {
+        headers,
+        inner: APIResponse {
+            json: Json(game),
+            status: Status::Ok,
+        },
+    })
+}
+
+/// Reads the board out of a game that has had a JSON Patch applied to it
+///
+/// Clients may patch either the flat board or the grid, a changed grid takes precedence.
+///
+/// Returns None if the patched board or grid is not made of strings
+///
+/// # Arguments
+///
+/// * 'original' - The game as JSON before the patch
+///
+/// * 'patched' - The game as JSON after the patch
+fn patched_board(original: &Value, patched: &Value) -> Option<String> {
+    if patched["grid"] == original["grid"] {
+        return patched["board"].as_str().map(String::from);
+    }
+    let mut board = String::new();
+    for row in patched["grid"].as_array()? {
+        for cell in row.as_array()? {
+            board.push_str(cell.as_str()?);
+        }
+    }
+    Some(board)
+}
+
+/// Applies the JSON Patch to the game and reads the patched board, see patch_game
+///
+/// The patch is written against the game as clients see it, in the configured marks.
+///
+/// Returns the patched board, or the response the patch is rejected with as an error
+///
+/// # Arguments
+///
+/// * 'game' - The game the patch is applied to
+///
+/// * 'patch' - List of JSON Patch operations
+///
+/// * 'marks' - Marks the board is shown with to clients
+fn patched_move(
+    game: &Game,
+    patch: &Patch,
+    marks: (char, char),
+) -> Result<String, APIResponse<ErrorMessage>> {
+    let mut original = match json::to_value(game) {
+        Ok(value) => value,
+        Err(e) => return Err(ErrorMessage::response(Status::InternalServerError, e)),
+    };
+    localize_marks(&mut original, marks);
+    let mut patched = original.clone();
+    if let Err(e) = json_patch::patch(&mut patched, patch) {
+        return Err(ErrorMessage::response(Status::UnprocessableEntity, e));
+    }
+    match patched_board(&original, &patched) {
+        Some(board) => Ok(board),
+        None => Err(ErrorMessage::response(
+            Status::UnprocessableEntity,
+            "Patched board is not a string",
+        )),
+    }
+}
+
+/// Makes a move by applying an RFC 6902 JSON Patch to the game.
+///
+/// The patch is applied to the game as it is returned by the API, usually a replace of the board or a
+/// single cell of the grid. The patched board is then validated as a single move like a PUT request
+/// and the computer replies. Patches that fail to apply are rejected with 422 Unprocessable Entity,
+/// illegal moves with the same statuses as in put_player_move.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'patch' - Payload in the PATCH request, a list of JSON Patch operations
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'if_match' - ETag the client expects the game to have, if any
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// * 'move_token' - Move token supplied by the client, required in PvP games
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[patch(
+    "/games/<id>",
+    format = "application/json-patch+json",
+    data = "<patch>"
+)]
+async fn patch_game(
+    id: String,
+    patch: Json<Patch>,
+    game_list: &State<GameList>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    watchers: &State<Watchers>,
+    if_match: IfMatch,
+    tokens: &State<TokenList>,
+    move_token: MoveToken,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<APIResponse<Game>>, APIResponse<ErrorMessage>> {
+    // Letting the computer "think" before it replies, requests it won't reply to aren't held up
+    let replies = game_list
+        .inner()
+        .list
+        .lock()
+        .unwrap()
+        .get(&*id)
+        .is_some_and(|game| {
+            if_match.matches(&game.get_etag())
+                && patched_move(game, &patch.0, config.marks)
+                    .is_ok_and(|new_board| game.replies_to(&new_board, config))
+        });
+    if replies {
+        computer_move_delay(config).await;
+    }
+
+    let game_id = id.clone();
+    let games = game_list.inner().clone();
+    let (settings, registry) = (config.inner().clone(), strategies.inner().clone());
+    let move_tokens = tokens.move_tokens(&id);
+
+    // The move is made on a copy that replaces the stored game only if it's done in time, as in put_player_move
+    let (outcome, game) = deadline::run(config.request_timeout, move |deadline| {
+        let mut guard = games.list.lock().unwrap();
+        let mut current_game = match guard.get(&*game_id) {
+            Some(game) => game.clone(),
+            None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+        };
+        // Rejecting moves made against an outdated version of the game
+        if !if_match.matches(&current_game.get_etag()) {
+            return Err(ErrorMessage::response(
+                Status::PreconditionFailed,
+                "Game has changed since the given ETag",
+            ));
+        }
+        if !may_move(&move_tokens, &current_game.get_board(), &move_token) {
+            return Err(ErrorMessage::response(
+                Status::Forbidden,
+                "Move token is not the token of the side to move",
+            ));
+        }
+
+        let new_board = patched_move(&current_game, &patch.0, settings.marks)?;
+        let outcome = current_game
+            .make_move(new_board, &settings, &registry)
+            .map_err(|e| ErrorMessage::response(move_error_status(&e), e))?;
+        if deadline.claim() {
+            let mut stored_game = current_game.clone();
+            stored_game.clear_computer_move_kind();
+            guard.insert(game_id, stored_game);
+        }
+        Ok((outcome, current_game))
+    })
+    .await
+    .map_err(|status| {
+        let reason = if status == Status::ServiceUnavailable {
+            "Unable to make move: request timed out"
+        } else {
+            "Unable to make move"
+        };
+        ErrorMessage::response(status, reason)
+    })??;
+
+    transcript::record(&game, config);
+    watchers.notify(&id);
+    let mut headers = vec![Header::new("ETag", game.get_etag())];
+    if let Some(reply) = &outcome.computer_move {
+        headers.push(Header::new("X-Computer-Move", reply.index.to_string()));
+    }
+    Ok(WithHeaders {
+        headers,
+        inner: APIResponse {
+            json: Json(game),
+            status: Status::Ok,
+        },
+    })
+}
+
+/// Previews a move without committing it.
+///
+/// Applies the submitted move and the computer's reply to a copy of the game using the same logic as
+/// a real move, and returns the resulting game. The stored game is left unchanged.
+/// Payloads are rejected with the same statuses as in put_player_move.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'game' - Payload in the POST request, contains to game object with an updated board. (Player move)
+///   Or the reason it couldn't be read as a game
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/simulate", format = "json", data = "<game>")]
+fn simulate_move(
+    id: String,
+    game_list: &State<GameList>,
+    game: Result<Json<Game>, json::Error<'_>>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+) -> Result<APIResponse<Game>, Status> {
+    let game = game.map_err(|_| Status::BadRequest)?;
+    let mut simulated_game = match game_list.inner().list.lock().unwrap().get(&*id) {
+        Some(current_game) => current_game.clone(),
+        None => return Err(Status::NotFound),
+    };
+
+    let new_board = game.get_board();
+    if let Err(e) = simulated_game.make_move(new_board, config, strategies) {
+        return Err(move_error_status(&e));
+    }
+
+    Ok(APIResponse {
+        json: Json(simulated_game),
+        status: Status::Ok,
+    })
+}
+
+/// Plays a game marked for autoplay to completion, with the computer making the moves for both sides.
+///
+/// Returns the finished game
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/autoplay")]
+fn autoplay_game(
+    id: String,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    config: &State<Config>,
+) -> Result<APIResponse<Game>, Status> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(Status::NotFound),
+    };
+
+    if let Err(e) = current_game.autoplay() {
+        warn!("Unable to autoplay game {}: {}", id, e);
+        return Err(Status::BadRequest);
+    }
+    transcript::record(current_game, config);
+    watchers.notify(&id);
+
+    Ok(APIResponse {
+        json: Json(current_game.clone()),
+        status: Status::Ok,
+    })
+}
+
+/// Result of playing a sequence of moves
+#[derive(Serialize)]
+struct PlayedMoves {
+    /// The game after the played moves
+    game: Game,
+    /// Amount of moves that were played
+    applied: usize,
+    /// Reason the next move was rejected, None if the sequence was played or the game ended
+    error: Option<String>,
+}
+
+/// Plays a sequence of moves given as cell indexes, with the computer replying between each.
+///
+/// Playing stops at the first illegal move or once the game is over. Reduces round-trips for clients
+/// replaying a known line. PvP games are refused with 403 Forbidden, as their sides move with separate
+/// tokens.
+///
+/// Returns the resulting game along with the amount of moves played
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'moves' - Payload in the POST request, indexes of the cells to play in order
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// * '_rate_limited' - Rejects clients that exceed the configured rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/moves", format = "json", data = "<moves>")]
+fn play_moves(
+    id: String,
+    moves: Json<Vec<usize>>,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    tokens: &State<TokenList>,
+    _rate_limited: RateLimited,
+) -> Result<APIResponse<PlayedMoves>, Status> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(Status::NotFound),
+    };
+    if tokens.move_tokens(&id).is_some() {
+        return Err(Status::Forbidden);
+    }
+
+    let (applied, error) = current_game.play_moves(&moves, config, strategies);
+    if applied > 0 {
+        transcript::record(current_game, config);
+        watchers.notify(&id);
+    }
+
+    let game = current_game.clone();
+    current_game.clear_computer_move_kind();
+
+    Ok(APIResponse {
+        json: Json(PlayedMoves {
+            game,
+            applied,
+            error: error.map(|e| e.to_string()),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Payload of a board conversion request
+#[derive(Deserialize)]
+struct ConvertRequest {
+    /// The board written in the source format
+    board: String,
+    /// Format the board is written in
+    from: BoardFormat,
+    /// Format to convert the board to
+    to: BoardFormat,
+}
+
+/// Result of a board conversion
+#[derive(Serialize)]
+struct ConvertedBoard {
+    /// The board written in the target format
+    board: String,
+}
+
+/// Converts a board between the supported text formats for interop with other tools.
+///
+/// The board must be a legal position, the same rules apply as for resuming a game.
+///
+/// # Arguments
+///
+/// * 'req' - POST request payload, contains the board and the source and target formats
+///
+/// * 'config' - Runtime configuration of the API
+#[post("/convert", format = "json", data = "<req>")]
+fn convert_board(
+    req: Json<ConvertRequest>,
+    config: &State<Config>,
+) -> Result<APIResponse<ConvertedBoard>, APIResponse<ErrorMessage>> {
+    let board = internal_marks(&req.board, config.marks);
+    let board = match formats::parse(&board, req.from, &config.empty_aliases) {
+        Ok(board) => board,
+        Err(e) => {
+            let status = create_error_status(&e);
+            return Err(ErrorMessage::response(
+                status,
+                format!("Unable to convert board: {}", e.reason()),
+            ));
+        }
+    };
+
+    Ok(APIResponse {
+        json: Json(ConvertedBoard {
+            board: formats::render(&board, req.to),
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Payload of a move validation request
+#[derive(Deserialize)]
+struct MoveCheck {
+    /// The board before the move
+    board: String,
+    /// The board after the move, either this or index must be given
+    #[serde(default)]
+    new_board: Option<String>,
+    /// Index of the cell the side to move places its sign in, either this or new_board must be given
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+/// Result of a move validation
+#[derive(Serialize)]
+struct MoveValidity {
+    /// Whether the move is a legal single move
+    legal: bool,
+    /// Sign of the side to move on the board before the move
+    sign: Sign,
+    /// Index of the cell the move places a sign in, None if the move is illegal
+    index: Option<usize>,
+    /// Reason the move is illegal, None if it's legal
+    reason: Option<String>,
+}
+
+/// Checks whether a move is legal without a stored game, for client-side UIs validating moves before
+/// submitting them.
+///
+/// The move is given as the board after it or as the index of the cell played. It must place a single
+/// sign of the side to move, which is the side with fewer signs or X when both have as many, using the
+/// same validation as a move in a game. Boards are read like submitted boards and the board before the
+/// move must be a legal position, otherwise the request is rejected as when creating a game.
+///
+/// # Arguments
+///
+/// * 'req' - POST request payload, contains the board before the move and the move
+///
+/// * 'config' - Runtime configuration of the API
+#[post("/validate-move", format = "json", data = "<req>")]
+fn check_move(
+    req: Json<MoveCheck>,
+    config: &State<Config>,
+) -> Result<APIResponse<MoveValidity>, APIResponse<ErrorMessage>> {
+    let board = prepare_board(&req.board, config);
+    if let Err(e) = validate_position(&board) {
+        let status = create_error_status(&e);
+        return Err(ErrorMessage::response(
+            status,
+            format!("Unable to validate move: {}", e.reason()),
+        ));
+    }
+    let sign = side_to_move(&board, Sign::X);
+
+    let new_board = match (&req.new_board, req.index) {
+        (Some(new_board), None) => prepare_board(new_board, config),
+        (None, Some(index)) if index < 9 => {
+            // Writing the sign over a placed one lets the validation report the overwrite
+            let mut new_board = board.clone();
+            new_board.replace_range(index..index + 1, sign.as_str());
+            new_board
+        }
+        (None, Some(_)) => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                "Unable to validate move: index must be between 0 and 8",
+            ))
+        }
+        _ => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                "Unable to validate move: exactly one of new_board and index must be given",
+            ))
+        }
+    };
+
+    let result = match evaluate_board(&board) {
+        GameStatus::RUNNING => validate_move(&board, &new_board, sign),
+        _ => Err(MoveError::GameOver),
+    };
+    let validity = match result {
+        Ok(index) => MoveValidity {
+            legal: true,
+            sign,
+            index: Some(index),
+            reason: None,
+        },
+        Err(e) => MoveValidity {
+            legal: false,
+            sign,
+            index: None,
+            reason: Some(e.to_string()),
+        },
+    };
+
+    Ok(APIResponse {
+        json: Json(validity),
+        status: Status::Ok,
+    })
+}
+
+/// Plays a number of self-play games between two strategies and returns the aggregate results.
+///
+/// The games are not stored. The amount of games is capped to MAX_TOURNAMENT_GAMES and
+/// the games are played on a blocking thread as minimax from an empty board is expensive.
+///
+/// Fails with 400 Bad Request if no strategy is registered with either name
+///
+/// # Arguments
+///
+/// * 'tournament' - POST request payload, contains the amount of games and the strategies of both signs
+///
+/// * 'strategies' - Registry of the strategies the sides can choose their moves with
+///
+/// * '_rate_limited' - Rejects clients that exceed the configured rate limit
+#[post("/tournament", data = "<tournament>")]
+async fn play_tournament(
+    tournament: Json<Tournament>,
+    strategies: &State<Strategies>,
+    _rate_limited: RateLimited,
+) -> Result<APIResponse<TournamentResult>, APIResponse<ErrorMessage>> {
+    let tournament = tournament.into_inner();
+    let registry = strategies.inner().clone();
+    let result = rocket::tokio::task::spawn_blocking(move || tournament.play(&registry))
+        .await
+        .map_err(|_| {
+            ErrorMessage::response(Status::InternalServerError, "Unable to play tournament")
+        })?;
+
+    match result {
+        Ok(result) => Ok(APIResponse {
+            json: Json(result),
+            status: Status::Ok,
+        }),
+        Err(e) => Err(ErrorMessage::response(
+            create_error_status(&e),
+            format!("Unable to play tournament: {}", e.reason()),
+        )),
+    }
+}
+
+/// Takes back the computer's last reply, leaving the player's move before it on the board.
+///
+/// With reply=true the computer immediately replies again, which may pick a different cell. Without it
+/// the game waits for the computer and moves by the player are rejected until it replies.
+///
+/// Returns the updated game
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'reply' - Optional query parameter, whether the computer makes a new reply after the undo
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/undo-computer?<reply>")]
+fn undo_computer(
+    id: String,
+    reply: Option<bool>,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    strategies: &State<Strategies>,
+) -> Result<APIResponse<Game>, APIResponse<ErrorMessage>> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+
+    if let Err(e) = current_game.undo_computer_move(reply.unwrap_or(false), strategies) {
+        return Err(ErrorMessage::response(Status::Conflict, e));
+    }
+    watchers.notify(&id);
+
+    Ok(APIResponse {
+        json: Json(current_game.clone()),
+        status: Status::Ok,
+    })
+}
+
+/// Makes one computer move for the side to move without a move by the player.
+///
+/// Lets the computer make a reply that was undone without reply=true, or play a move for either side
+/// of an analysis game. PvP games are refused with 403 Forbidden, as their sides move with separate
+/// tokens.
+///
+/// Returns the updated game, with the cell the computer played in the X-Computer-Move header
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/computer-move")]
+fn step_computer(
+    id: String,
+    game_list: &State<GameList>,
+    watchers: &State<Watchers>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    tokens: &State<TokenList>,
+) -> Result<WithHeaders<APIResponse<Game>>, APIResponse<ErrorMessage>> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+    if tokens.move_tokens(&id).is_some() {
+        return Err(ErrorMessage::response(
+            Status::Forbidden,
+            "Unable to move: the computer doesn't move in PvP games",
+        ));
+    }
+
+    let computer_move = match current_game.step_computer(strategies) {
+        Ok(computer_move) => computer_move,
+        Err(e) => return Err(ErrorMessage::response(Status::Conflict, e)),
+    };
+    if current_game.is_over() {
+        transcript::record(current_game, config);
+    }
+    watchers.notify(&id);
+
+    let game = current_game.clone();
+    current_game.clear_computer_move_kind();
+
+    Ok(WithHeaders {
+        headers: vec![
+            Header::new("ETag", game.get_etag()),
+            Header::new("X-Computer-Move", computer_move.index.to_string()),
+        ],
+        inner: APIResponse {
+            json: Json(game),
+            status: Status::Ok,
+        },
+    })
+}
+
+/// Swaps the sides of the player and the computer after the computer's opening move (pie rule).
+///
+/// The player takes over the sign of the opening move and the computer replies with the other sign right
+/// away, so the player is to move after the swap. Rejected with 409 Conflict once the game is over or
+/// anything but the computer's opening move has been played.
+///
+/// Returns the updated game
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/swap")]
+fn swap_sides(
+    id: String,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    watchers: &State<Watchers>,
+    strategies: &State<Strategies>,
+) -> Result<APIResponse<Game>, APIResponse<ErrorMessage>> {
+    let mut guard = game_list.inner().list.lock().unwrap();
+    let current_game = match guard.get_mut(&*id) {
+        Some(game) => game,
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+
+    if let Err(e) = current_game.swap_sides(player_signs, strategies) {
+        return Err(ErrorMessage::response(Status::Conflict, e));
+    }
+    watchers.notify(&id);
+
+    Ok(APIResponse {
+        json: Json(current_game.clone()),
+        status: Status::Ok,
+    })
+}
+
+/// Base URL of the API that game URLs are built on
+/// Would be set to actual host adress in prod with env variable
+const BASE_URL: &str = "http://127.0.0.1:8000/";
+
+/// Builds the URL of a game, exactly `<base>/games/<id>`
+///
+/// The ID is appended as a path segment rather than joined as a relative reference, so it is
+/// percent-encoded if needed and can't escape the games path.
+///
+/// # Arguments
+///
+/// * 'id' - ID of the game
+fn game_url(id: &str) -> Result<Url, url::ParseError> {
+    resource_url("games", id)
+}
+
+/// Builds the URL of a match, exactly `<base>/matches/<id>`, see game_url
+///
+/// # Arguments
+///
+/// * 'id' - ID of the match
+fn match_url(id: &str) -> Result<Url, url::ParseError> {
+    resource_url("matches", id)
+}
+
+/// Builds the URL of a resource in a collection, exactly `<base>/<collection>/<id>`, see game_url
+///
+/// # Arguments
+///
+/// * 'collection' - Path segment of the collection
+///
+/// * 'id' - ID of the resource
+fn resource_url(collection: &str, id: &str) -> Result<Url, url::ParseError> {
+    let mut url = Url::parse(BASE_URL)?;
+    url.path_segments_mut()
+        .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+        .pop_if_empty()
+        .extend([collection, id]);
+    Ok(url)
+}
+
+/// Standings of a match along with the game being played
+#[derive(Serialize)]
+struct MatchStandings {
+    /// The match
+    #[serde(flatten)]
+    standings: Match,
+    /// The game being played, None once the match is over
+    current_game: Option<Game>,
+}
+
+/// Creates a best of N match between the player and the computer and starts its first game.
+///
+/// The games of the match are regular games, see match_standings for how the match advances.
+///
+/// Returns the URL of the match, both in the body and in the Location header
+///
+/// # Arguments
+///
+/// * 'new_match' - POST request payload, contains the amount of games and the options of every game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'match_list' - Maintains a map of all matches in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/matches", format = "json", data = "<new_match>")]
+fn new_match(
+    new_match: Json<NewMatch>,
+    game_list: &State<GameList>,
+    match_list: &State<MatchList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<APIResponse<Url>>, APIResponse<ErrorMessage>> {
+    // An even amount of games could end level without a single draw
+    if new_match.best_of.is_multiple_of(2) || new_match.best_of > MAX_BEST_OF {
+        return Err(ErrorMessage::response(
+            Status::BadRequest,
+            format!(
+                "Unable to create match: best_of must be odd and at most {}",
+                MAX_BEST_OF
+            ),
+        ));
+    }
+    // Checked under the lock the first game is stored with, so concurrent requests can't overfill the store
+    let mut games = game_list.inner().list.lock().unwrap();
+    if games.len() >= config.max_games {
+        return Err(ErrorMessage::response(
+            Status::ServiceUnavailable,
+            "Unable to create match: maximum number of games reached",
+        ));
+    }
+
+    let series = match Match::new(&new_match, &mut games, player_signs, config, strategies) {
+        Ok(series) => series,
+        // Only the strategy and sign of the games can be wrong, the board is always empty
+        Err(e) => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                format!("Unable to create match: {}", e.reason()),
+            ))
+        }
+    };
+    // Released before the match list is locked, which is locked before the game list elsewhere
+    drop(games);
+    let id = series.get_id().clone();
+    let url = match match_url(&id) {
+        Ok(url) => url,
+        Err(e) => return Err(ErrorMessage::response(Status::InternalServerError, e)),
+    };
+    match_list.list.lock().unwrap().insert(id, series);
+
+    Ok(WithHeaders {
+        headers: vec![Header::new("Location", url.to_string())],
+        inner: APIResponse {
+            json: Json(url),
+            status: Status::Created,
+        },
+    })
+}
+
+/// Returns the standings of a match along with the game being played.
+///
+/// The match advances when its standings are fetched: if the current game has ended, its result is
+/// recorded and the next game is started unless the match has been decided.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the match
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'match_list' - Maintains a map of all matches in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[get("/matches/<id>")]
+fn match_standings(
+    id: String,
+    game_list: &State<GameList>,
+    match_list: &State<MatchList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+) -> Result<APIResponse<MatchStandings>, Status> {
+    let mut matches = match_list.list.lock().unwrap();
+    let series = match matches.get_mut(&*id) {
+        Some(series) => series,
+        None => return Err(Status::NotFound),
+    };
+    // The options were checked when the match was created, so the next game can always be created
+    if series
+        .advance(game_list, player_signs, config, strategies)
+        .is_err()
+    {
+        return Err(Status::InternalServerError);
+    }
+
+    let current_game = series
+        .current_game()
+        .and_then(|game_id| game_list.inner().list.lock().unwrap().get(game_id).cloned());
+    Ok(APIResponse {
+        json: Json(MatchStandings {
+            standings: series.clone(),
+            current_game,
+        }),
+        status: Status::Ok,
+    })
+}
+
+/// Returns whether the owner already has the configured maximum amount of running games
+///
+/// Finished and archived games don't count towards the limit. Always false if no limit is configured
+/// or the client didn't send an owner token, as a new token owns no games.
+///
+/// # Arguments
+///
+/// * 'owner' - Owner token supplied by the client
+///
+/// * 'games' - The stored games by ID
+///
+/// * 'tokens' - Maintains a map of the read-only and owner tokens of each game in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+fn owner_at_limit(
+    owner: &OwnerToken,
+    games: &HashMap<String, Game>,
+    tokens: &TokenList,
+    config: &Config,
+) -> bool {
+    let token = match &owner.0 {
+        Some(token) if config.max_games_per_owner > 0 => token,
+        _ => return false,
+    };
+    let running = tokens
+        .owned_games(token)
+        .iter()
+        .filter_map(|id| games.get(id))
+        .filter(|game| !game.is_over() && !game.is_archived())
+        .count();
+    running >= config.max_games_per_owner
+}
+
+/// Request guard reading the optional return query parameter of a game creation request, which tells
+/// what the response body holds: "url" for the URL of the game or "game" for the game itself
+///
+/// Never fails, the value is validated by the handler so it can answer with a JSON error.
+struct CreatedBody(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for CreatedBody {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let returns = req
+            .query_value::<String>("return")
+            .map(|value| value.unwrap_or_default());
+        request::Outcome::Success(CreatedBody(returns))
+    }
+}
+
+/// Response of a game creation request
+#[allow(clippy::large_enum_variant)]
+#[derive(Responder)]
+enum CreatedResponse {
+    /// URL of the created game, the default
+    Url(APIResponse<Url>),
+    /// The created game, asked for with return=game
+    Game(APIResponse<Game>),
+}
+
+/// Creates a new game with a board as defined in the POST request payload
+///
+/// The handler will validate a user defined first move and provide a response move from the computer
+///
+/// A read-only token for sharing the game with spectators is returned in the X-Read-Token header.
+/// The owner token of the client is returned in the X-Owner-Token header, clients can send it back in
+/// the same header when creating further games to group them under the same owner. Browsers get it in
+/// a signed session cookie as well, which is used as the owner token when the header is missing.
+/// PvP games get a move token for each side, returned in the X-Move-Token-X and X-Move-Token-O headers.
+/// The X-New-Game header tells whether the computer opened the game or the player had already moved.
+/// The URL of the game is returned in the body and in the Location header, with return=game the body
+/// holds the created game instead, along with the same flag in its is_new field.
+///
+/// Without a body the game is created from the configured default board with no other options.
+///
+/// # Arguments
+///
+/// * 'returns' - Parsed from the query, what the response body holds
+///
+/// * 'board' - POST request payload, contains a representation of the game board and creation options.
+///   Or the reason it couldn't be read, which is an empty body for a bare request
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'strategies' - Registry of the strategies the computer can choose its moves with
+///
+/// * 'tokens' - Maintains a map of the read-only and owner tokens of each game in a mutex to handle async requests
+///
+/// * 'owner' - Owner token supplied by the client, a new one is generated if missing
+///
+/// * 'cookies' - Cookies of the request, the session cookie is set to the owner token
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games", data = "<board>")]
+async fn new_game(
+    returns: CreatedBody,
+    board: Result<Json<NewGame>, json::Error<'_>>,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    strategies: &State<Strategies>,
+    tokens: &State<TokenList>,
+    owner: OwnerToken,
+    cookies: &CookieJar<'_>,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<CreatedResponse>, APIResponse<ErrorMessage>> {
+    let return_game = match returns.0.as_deref() {
+        None | Some("url") => false,
+        Some("game") => true,
+        Some(other) => {
+            return Err(ErrorMessage::response(
+                Status::BadRequest,
+                format!(
+                    "Unable to create game: return must be url or game, got {}",
+                    other
+                ),
+            ))
+        }
+    };
+    let board = match board {
+        Ok(board) => board.into_inner(),
+        Err(json::Error::Parse(body, _)) if body.trim().is_empty() => {
+            NewGame::with_board(config.default_board.clone())
+        }
+        // Statuses as Rocket answers JSON it can't read
+        Err(json::Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            return Err(ErrorMessage::response(
+                Status::PayloadTooLarge,
+                "Request body is too large",
+            ))
+        }
+        Err(json::Error::Parse(_, e)) if e.is_data() => {
+            return Err(ErrorMessage::response(Status::UnprocessableEntity, e))
+        }
+        Err(e) => return Err(ErrorMessage::response(Status::BadRequest, e)),
+    };
+
+    // Refusing new games once the store is full to bound memory use
+    {
+        let list = game_list.inner().list.lock().unwrap();
+        if list.len() >= config.max_games {
+            return Err(ErrorMessage::response(
+                Status::ServiceUnavailable,
+                "Unable to create game: maximum number of games reached",
+            ));
+        }
+        if owner_at_limit(&owner, &list, tokens, config) {
+            return Err(ErrorMessage::response(
+                Status::TooManyRequests,
+                "Unable to create game: maximum number of running games per owner reached",
+            ));
+        }
+    }
+
+    // Pulling player map in
+    let _player_map = &player_signs.inner().player_map;
+
+    // Creating new game object with the board and options in the request, the computer's opening move
+    // may take long enough to time out in which case the game is dropped
+    let (players, settings) = (player_signs.inner().clone(), config.inner().clone());
+    let registry = strategies.inner().clone();
+    let pvp = board.is_pvp();
+    let try_new_game = deadline::run(config.request_timeout, move |deadline| {
+        let created = Game::new(&board, &players, &settings, &registry);
+        if let Ok(game) = &created {
+            if !deadline.claim() {
+                let id = game.get_id().clone().unwrap();
+                players.player_map.lock().unwrap().remove(&id);
+            }
+        }
+        created
+    })
+    .await
+    .map_err(|status| {
+        let reason = if status == Status::ServiceUnavailable {
+            "Unable to create game: request timed out"
+        } else {
+            "Unable to create game"
+        };
+        ErrorMessage::response(status, reason)
+    })?;
+    let new_game = match try_new_game {
+        Ok(valid_game) => valid_game,
+        Err(e) => {
+            println!("{}", e);
+            let status = create_error_status(&e);
+            return Err(ErrorMessage::response(status, e));
+        }
+    };
+
+    // Letting the computer "think" about its opening move or reply before the game is stored and returned
+    if new_game
+        .get_history()
+        .iter()
+        .any(|made| made.by == "COMPUTER")
+    {
+        computer_move_delay(config).await;
+    }
+
+    // Getting game id for use in map of games and url
+    let id = new_game.get_id().clone().unwrap();
+    let is_new = new_game.is_new();
+
+    // redirecting to game, built before the game is stored so a failure doesn't leave it behind
+    let game_url = match game_url(&id) {
+        Ok(url) => url,
+        Err(e) => {
+            println!("{}", e);
+            return Err(ErrorMessage::response(Status::InternalServerError, e));
+        }
+    };
+
+    // Checked again and stored under one lock, other games may have been stored while this one was created
+    let mut list = game_list.inner().list.lock().unwrap();
+    if list.len() >= config.max_games {
+        player_signs.inner().player_map.lock().unwrap().remove(&id);
+        return Err(ErrorMessage::response(
+            Status::ServiceUnavailable,
+            "Unable to create game: maximum number of games reached",
+        ));
+    }
+    if owner_at_limit(&owner, &list, tokens, config) {
+        player_signs.inner().player_map.lock().unwrap().remove(&id);
+        return Err(ErrorMessage::response(
+            Status::TooManyRequests,
+            "Unable to create game: maximum number of running games per owner reached",
+        ));
+    }
+    let read_token = tokens.issue_read_token(&id);
+    let owner_token = tokens.set_owner(&id, owner.0);
+    start_session(cookies, &owner_token);
+    let mut headers = vec![
+        Header::new("Location", game_url.to_string()),
+        Header::new("X-Read-Token", read_token),
+        Header::new("X-Owner-Token", owner_token),
+        Header::new("X-New-Game", is_new.to_string()),
+    ];
+    if pvp {
+        let (x_token, o_token) = tokens.issue_move_tokens(&id);
+        headers.push(Header::new("X-Move-Token-X", x_token));
+        headers.push(Header::new("X-Move-Token-O", o_token));
+    }
+
+    let inner = if return_game {
+        let mut created = new_game.clone();
+        created.report_is_new();
+        CreatedResponse::Game(APIResponse {
+            json: Json(created),
+            status: Status::Created,
+        })
+    } else {
+        CreatedResponse::Url(APIResponse {
+            json: Json(game_url),
+            status: Status::Created,
+        })
+    };
+
+    // Adding game to map
+    list.insert(id, new_game);
+
+    Ok(WithHeaders { headers, inner })
+}
+
+/// Copies an existing game into a new game with its own ID for branching analysis.
+///
+/// The copy is independent of the source game, moves made in one do not affect the other.
+/// Tokens for the copy are issued the same way as for a newly created game, copies of PvP games get
+/// new move tokens for each side in the X-Move-Token-X and X-Move-Token-O headers.
+///
+/// Returns URL to the copy
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the source game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// * 'owner' - Owner token supplied by the client, a new one is generated if missing
+///
+/// * 'cookies' - Cookies of the request, the session cookie is set to the owner token
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/<id>/clone")]
+fn clone_game(
+    id: String,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    tokens: &State<TokenList>,
+    owner: OwnerToken,
+    cookies: &CookieJar<'_>,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<APIResponse<Url>>, APIResponse<ErrorMessage>> {
+    let mut list = game_list.inner().list.lock().unwrap();
+    if list.len() >= config.max_games {
+        return Err(ErrorMessage::response(
+            Status::ServiceUnavailable,
+            "Unable to clone game: maximum number of games reached",
+        ));
+    }
+    if owner_at_limit(&owner, &list, tokens, config) {
+        return Err(ErrorMessage::response(
+            Status::TooManyRequests,
+            "Unable to clone game: maximum number of running games per owner reached",
+        ));
+    }
+    let copy = match list.get(&*id) {
+        Some(game) => game.duplicate(player_signs),
+        None => return Err(ErrorMessage::response(Status::NotFound, "Game not found")),
+    };
+
+    let copy_id = copy.get_id().clone().unwrap();
+    let game_url = match game_url(&copy_id) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("Unable to build the URL of the copy of game {}: {}", id, e);
+            return Err(ErrorMessage::response(Status::InternalServerError, e));
+        }
+    };
+    let read_token = tokens.issue_read_token(&copy_id);
+    let owner_token = tokens.set_owner(&copy_id, owner.0);
+    start_session(cookies, &owner_token);
+    let mut headers = vec![
+        Header::new("Location", game_url.to_string()),
+        Header::new("X-Read-Token", read_token),
+        Header::new("X-Owner-Token", owner_token),
+    ];
+    // Copies of PvP games get their own move tokens, the source game's tokens don't move in the copy
+    if tokens.move_tokens(&id).is_some() {
+        let (x_token, o_token) = tokens.issue_move_tokens(&copy_id);
+        headers.push(Header::new("X-Move-Token-X", x_token));
+        headers.push(Header::new("X-Move-Token-O", o_token));
+    }
+    list.insert(copy_id, copy);
+
+    Ok(WithHeaders {
+        headers,
+        inner: APIResponse {
+            json: Json(game_url),
+            status: Status::Created,
+        },
+    })
+}
+
+/// Creates a game from a transcript in the notation of GET /games/<id>/notation and returns it.
+///
+/// The moves are replayed from an empty board, the player takes the side to move after the last move.
+/// Tokens for the game are issued the same way as for a newly created game.
+///
+/// # Arguments
+///
+/// * 'notation' - Payload in the POST request, the moves as sign and cell index pairs, e.g. "X:4 O:0"
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'config' - Runtime configuration of the API
+///
+/// * 'tokens' - Maintains a map of the read-only and owner tokens of each game in a mutex to handle async requests
+///
+/// * 'owner' - Owner token supplied by the client, a new one is generated if missing
+///
+/// * 'cookies' - Cookies of the request, the session cookie is set to the owner token
+///
+/// * '_rate_limited' - Request guard rejecting clients that have exceeded the rate limit
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[post("/games/import", data = "<notation>")]
+fn import_game(
+    notation: String,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    config: &State<Config>,
+    tokens: &State<TokenList>,
+    owner: OwnerToken,
+    cookies: &CookieJar<'_>,
+    _rate_limited: RateLimited,
+) -> Result<WithHeaders<APIResponse<Game>>, APIResponse<ErrorMessage>> {
+    let mut list = game_list.inner().list.lock().unwrap();
+    if list.len() >= config.max_games {
+        return Err(ErrorMessage::response(
+            Status::ServiceUnavailable,
+            "Unable to import game: maximum number of games reached",
+        ));
+    }
+    if owner_at_limit(&owner, &list, tokens, config) {
+        return Err(ErrorMessage::response(
+            Status::TooManyRequests,
+            "Unable to import game: maximum number of running games per owner reached",
+        ));
+    }
+    let game = match Game::import(&notation, player_signs, config) {
+        Ok(game) => game,
+        Err(e) => {
+            // Moves that can't be played in order conflict with the rules of the game
+            let status = create_error_status(&e);
+            return Err(ErrorMessage::response(
+                status,
+                format!("Unable to import game: {}", e.reason()),
+            ));
+        }
+    };
+
+    let id = game.get_id().clone().unwrap();
+    let game_url = match game_url(&id) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("Unable to build the URL of imported game {}: {}", id, e);
+            return Err(ErrorMessage::response(Status::InternalServerError, e));
+        }
+    };
+    let read_token = tokens.issue_read_token(&id);
+    let owner_token = tokens.set_owner(&id, owner.0);
+    start_session(cookies, &owner_token);
+    list.insert(id, game.clone());
+
+    Ok(WithHeaders {
+        headers: vec![
+            Header::new("Location", game_url.to_string()),
+            Header::new("X-Read-Token", read_token),
+            Header::new("X-Owner-Token", owner_token),
+        ],
+        inner: APIResponse {
+            json: Json(game),
+            status: Status::Created,
+        },
+    })
+}
+
+/// Deletes a game from the list of games and returns it.
+///
+/// With soft=true the game is archived instead, it's kept but hidden from listings.
+/// Deleting the game removes its sign choice and tokens as well, archived games keep them.
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'soft' - Optional query parameter, whether the game is archived instead of removed
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// * 'player_signs' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+///
+/// * 'watchers' - Maintains a map of change notifiers for each game in a mutex to handle async requests
+///
+/// * 'tombstones' - Keeps a bounded record of recently deleted game IDs
+///
+/// * 'tokens' - Maintains a map of the read-only, owner and move tokens of each game in a mutex to handle async requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[delete("/games/<id>?<soft>")]
+fn delete_game(
+    id: String,
+    soft: Option<bool>,
+    game_list: &State<GameList>,
+    player_signs: &State<PlayerList>,
+    watchers: &State<Watchers>,
+    tombstones: &State<Tombstones>,
+    tokens: &State<TokenList>,
+) -> Result<APIResponse<Game>, Status> {
+    let lock = game_list.inner();
+    let mut list = lock.list.lock().unwrap();
+
+    if soft.unwrap_or(false) {
+        return match list.get_mut(&*id) {
+            Some(game) => {
+                game.archive();
+                watchers.notify(&id);
+                Ok(APIResponse {
+                    json: Json(game.clone()),
+                    status: Status::Ok,
+                })
+            }
+            None => Err(Status::NotFound),
+        };
+    }
+
+    let delete = list.remove(&*id);
+    watchers.remove(&id);
+    if delete.is_some() {
+        player_signs.inner().player_map.lock().unwrap().remove(&*id);
+        tokens.forget(&id);
+        tombstones.bury(&id);
+    }
+
+    match delete {
+        Some(game) => Ok(APIResponse {
+            json: Json(game),
+            status: Status::Ok,
+        }),
+        None => Err(Status::NotFound),
+    }
+}
+
+/// Describes the methods supported on the collection of games
+#[options("/games")]
+fn games_options() -> WithHeaders<Status> {
+    WithHeaders {
+        inner: Status::NoContent,
+        headers: vec![Header::new("Allow", "GET, POST, OPTIONS")],
+    }
+}
+
+/// Describes the methods supported on a single game
+///
+/// # Arguments
+///
+/// * 'id' - Parsed from the URL, ID of the game
+///
+/// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+///
+/// # Panics
+/// May panic if the the function is unable to open up the mutex
+#[options("/games/<id>")]
+fn game_options(id: String, game_list: &State<GameList>) -> Result<WithHeaders<Status>, Status> {
+    if !game_list.list.lock().unwrap().contains_key(&*id) {
+        return Err(Status::NotFound);
+    }
+
+    Ok(WithHeaders {
+        inner: Status::NoContent,
+        headers: vec![Header::new("Allow", "GET, PUT, PATCH, DELETE, OPTIONS")],
+    })
+}
+
+#[launch]
+fn rocket() -> _ {
+    build(Config::from_env())
+}
+
+/// Builds the server with the configuration, its state, fairings and routes
+///
+/// # Arguments
+///
+/// * 'config' - Runtime configuration of the API
+fn build(config: Config) -> Rocket<Build> {
+    // Without a configured secret the session cookies are signed with a key that lives as long as the store
+    let secret_key = config.session_secret.clone().unwrap_or_else(|| {
+        rand::random::<[u8; 32]>()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    });
+    // Tight body limit, a game payload is a 9 character board and a few small fields
+    let figment = rocket::Config::figment()
+        .merge(("limits.json", config.json_limit))
+        .merge(("secret_key", secret_key));
+
+    let game_list = GameList {
+        list: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let player_list = PlayerList {
+        player_map: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let watchers = Watchers {
+        notifiers: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    // Sweeping idle games in the background when a move timeout is configured
+    let (move_timeout, move_timeout_draw) = (config.move_timeout, config.move_timeout_draw);
+    let (sweep_games, sweep_watchers) = (game_list.clone(), watchers.clone());
+    let sweep = AdHoc::on_liftoff("Move timeout sweep", move |_| {
+        Box::pin(async move {
+            if move_timeout == 0 {
+                return;
+            }
+            rocket::tokio::spawn(async move {
+                loop {
+                    sleep(Duration::from_secs(1)).await;
+                    let forfeited = sweep_games.forfeit_idle_games(move_timeout, move_timeout_draw);
+                    for id in forfeited {
+                        info!("Game {} forfeited after the move timeout", id);
+                        sweep_watchers.notify(&id);
+                    }
+                }
+            });
+        })
+    });
+
+    let cors = Cors {
+        allowed_origins: config.cors_origins.clone(),
+    };
+    let strategies = Strategies::new(config.minimax_tie_break);
+
+    // Launching rocket
+    rocket::custom(figment)
+        .manage(RateLimiter::new(
+            config.rate_limit,
+            config.rate_limit_window,
+        ))
+        .manage(Tombstones::new(
+            config.tombstone_capacity,
+            config.tombstone_ttl,
+        ))
+        .manage(config)
+        .manage(game_list)
+        .manage(player_list)
+        .manage(MatchList {
+            list: Arc::new(Mutex::new(HashMap::new())),
         })
-        .mount("/", routes![index])
+        .manage(strategies)
+        .manage(watchers)
+        .manage(TokenList {
+            read_tokens: Mutex::new(HashMap::new()),
+            owner_tokens: Mutex::new(HashMap::new()),
+            move_tokens: Mutex::new(HashMap::new()),
+        })
+        .attach(sweep)
+        .attach(cors)
+        .register("/", catchers![payload_too_large])
+        .mount("/", routes![index, openapi_document])
         .mount(
             "/",
             routes![
                 all_games,
+                game_ids,
+                grouped_games,
+                owner_stats,
+                my_games,
                 game_board,
+                wait_for_change,
+                game_events,
+                view_game,
+                game_ascii,
+                game_notation,
+                game_compact,
+                game_at,
+                game_svg,
+                game_canonical,
+                game_repetitions,
+                game_eval,
+                game_ranked_moves,
+                game_tree,
+                game_reachable,
+                game_outcome,
+                game_distance_to_end,
+                game_threats,
+                game_bitboard,
+                game_forks,
+                game_win_type,
                 new_game,
                 put_player_move,
-                delete_game
+                patch_game,
+                simulate_move,
+                play_moves,
+                autoplay_game,
+                undo_computer,
+                step_computer,
+                swap_sides,
+                clone_game,
+                import_game,
+                search_games,
+                new_match,
+                match_standings,
+                delete_game,
+                games_options,
+                game_options
+            ],
+        )
+        .mount("/", routes![play_tournament, convert_board, check_move])
+        .mount(
+            "/",
+            routes![
+                reset_store,
+                collect_garbage,
+                export_store,
+                import_store,
+                store_stats,
+                server_config,
+                set_game_board,
+                set_game_status
             ],
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Header;
+    use rocket::local::blocking::{Client, LocalResponse};
+    use rocket::serde::json::{self, Value};
+    use rocket::tokio::time::sleep;
+    use std::time::Duration;
+
+    /// Starts a server with the default configuration
+    fn client() -> Client {
+        client_with(|_| {})
+    }
+
+    /// Log records of the crate, captured by CaptureLogs
+    static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    /// Logger keeping the records of the crate in LOGS so tests can assert on them
+    struct CaptureLogs;
+
+    impl log::Log for CaptureLogs {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                LOGS.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Installs CaptureLogs, before any server is built so Rocket's own logger doesn't take its place
+    fn capture_logs() {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&CaptureLogs).unwrap();
+            log::set_max_level(log::LevelFilter::Info);
+        });
+    }
+
+    /// Returns the captured log records containing the text
+    fn logged(text: &str) -> Vec<String> {
+        let logs = LOGS.lock().unwrap();
+        logs.iter()
+            .filter(|line| line.contains(text))
+            .cloned()
+            .collect()
+    }
+
+    /// Starts a server with the default configuration changed by the closure
+    fn client_with(configure: impl FnOnce(&mut Config)) -> Client {
+        capture_logs();
+        let mut config = Config::from_env();
+        configure(&mut config);
+        Client::tracked(build(config)).unwrap()
+    }
+
+    /// Creates a game from the JSON payload and returns its ID
+    fn create(client: &Client, body: &str) -> String {
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        id_of(&response)
+    }
+
+    /// Reads the ID of a created game out of the Location header
+    fn id_of(response: &LocalResponse) -> String {
+        let location = response.headers().get_one("Location").unwrap();
+        location.rsplit('/').next().unwrap().to_string()
+    }
+
+    /// Replaces the stored board of a game without validating it
+    fn store_board(client: &Client, id: &str, board: &str) {
+        let game_list = client.rocket().state::<GameList>().unwrap();
+        let mut games = game_list.list.lock().unwrap();
+        games.get_mut(id).unwrap().set_board(board.to_string());
+    }
+
+    /// Submits the board as a move with PUT
+    fn put_board<'c>(client: &'c Client, id: &str, board: &str) -> LocalResponse<'c> {
+        client
+            .put(format!("/games/{}", id))
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"board": "{}"}}"#, board))
+            .dispatch()
+    }
+
+    /// Reads a JSON response body
+    fn body(response: LocalResponse) -> Value {
+        response.into_json().unwrap()
+    }
+
+    #[test]
+    fn player_winning_move_ends_the_game_by_the_player() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "XX-OO----");
+
+        let response = put_board(&client, &id, "XXXOO----");
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["status"], "X_WON");
+        assert_eq!(game["game_over"], true);
+        assert_eq!(game["ended_by"], "PLAYER");
+    }
+
+    #[test]
+    fn computer_winning_reply_ends_the_game_by_the_computer() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        // The computer's only cell left completes its diagonal
+        store_board(&client, &id, "OXXXOO-X-");
+
+        let game = body(put_board(&client, &id, "OXXXOOXX-"));
+
+        assert_eq!(game["board"], "OXXXOOXXO");
+        assert_eq!(game["status"], "O_WON");
+        assert_eq!(game["game_over"], true);
+        assert_eq!(game["ended_by"], "COMPUTER");
+    }
+
+    #[test]
+    fn running_game_is_not_over() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        assert_eq!(game["game_over"], false);
+        assert_eq!(game["ended_by"], Value::Null);
+    }
+
+    #[test]
+    fn requests_over_the_rate_limit_are_rejected() {
+        let client = client_with(|config| config.rate_limit = 2);
+        let create = || {
+            client
+                .post("/games")
+                .remote("10.0.0.1:5000".parse().unwrap())
+                .header(ContentType::JSON)
+                .body(r#"{"board": "---------"}"#)
+                .dispatch()
+                .status()
+        };
+
+        assert_eq!(create(), Status::Created);
+        assert_eq!(create(), Status::Created);
+        assert_eq!(create(), Status::TooManyRequests);
+    }
+
+    #[test]
+    fn rotating_x_real_ip_doesnt_bypass_the_rate_limit_without_trust_proxy() {
+        let client = client_with(|config| config.rate_limit = 1);
+        let create = |ip: &'static str| {
+            client
+                .post("/games")
+                .remote("10.0.0.1:5000".parse().unwrap())
+                .header(Header::new("X-Real-IP", ip))
+                .header(ContentType::JSON)
+                .body(r#"{"board": "---------"}"#)
+                .dispatch()
+                .status()
+        };
+
+        assert_eq!(create("1.1.1.1"), Status::Created);
+        assert_eq!(create("2.2.2.2"), Status::TooManyRequests);
+    }
+
+    #[test]
+    fn oversized_bodies_are_rejected_with_a_json_error() {
+        let client = client();
+        let board = "X".repeat(1_000_000);
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"board": "{}"}}"#, board))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(body(response)["error"], "Request body is too large");
+    }
+
+    #[test]
+    fn ascii_board_is_rendered_as_a_grid() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "X---O----");
+
+        let response = client.get(format!("/games/{}/ascii", id)).dispatch();
+
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(
+            response.into_string().unwrap(),
+            "X | - | -\n---------\n- | O | -\n---------\n- | - | -\n"
+        );
+        let response = client.get("/games/unknown/ascii").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn svg_board_draws_a_glyph_per_sign() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "X---O----");
+
+        let response = client.get(format!("/games/{}/svg", id)).dispatch();
+
+        assert_eq!(response.content_type(), Some(ContentType::SVG));
+        let svg = response.into_string().unwrap();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains("<circle cx=\"150\" cy=\"150\""));
+        // Two grid lines each way and two strokes for the X
+        assert_eq!(svg.matches("<line").count(), 6);
+        let response = client.get("/games/unknown/svg").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn autoplay_plays_the_game_to_completion() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "autoplay": true}"#);
+
+        let response = client.post(format!("/games/{}/autoplay", id)).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["game_over"], true);
+        assert_ne!(game["status"], "RUNNING");
+        // The computer's own sign is played with minimax, so it never loses
+        let lost = match game["ended_by"].as_str() {
+            Some("PLAYER") => game["status"] != "DRAW",
+            _ => false,
+        };
+        assert!(!lost);
+    }
+
+    #[test]
+    fn autoplay_is_refused_for_games_not_marked_for_it() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+
+        let response = client.post(format!("/games/{}/autoplay", id)).dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(logged(&format!("Unable to autoplay game {}", id)).len(), 1);
+    }
+
+    #[test]
+    fn lowercase_signs_are_normalized() {
+        let client = client();
+        let id = create(&client, r#"{"board": "x--------"}"#);
+
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        let board = game["board"].as_str().unwrap();
+        assert!(board.starts_with('X'));
+        assert_eq!(board.matches('O').count(), 1);
+        assert!(!board.contains('x'));
+    }
+
+    #[test]
+    fn alternate_empty_cells_are_normalized() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X_ . _ ._"}"#);
+
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        let board = game["board"].as_str().unwrap();
+        assert!(board.starts_with('X'));
+        assert_eq!(board.matches('-').count(), 7);
+    }
+
+    #[test]
+    fn reset_empties_the_store() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        create(&client, r#"{"board": "---------"}"#);
+        create(&client, r#"{"board": "---------"}"#);
+
+        let response = client
+            .post("/admin/reset")
+            .header(Header::new("X-API-Key", "key"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let games = body(client.get("/games").dispatch());
+        assert_eq!(games, json::json!([]));
+    }
+
+    #[test]
+    fn reset_forgets_matches_deleted_games_and_rate_limits() {
+        let client = client_with(|config| {
+            config.api_key = Some(String::from("key"));
+            config.rate_limit = 2;
+        });
+        let create = || {
+            client
+                .post("/games")
+                .remote("10.0.0.1:5000".parse().unwrap())
+                .header(ContentType::JSON)
+                .body(r#"{"board": "---------"}"#)
+                .dispatch()
+        };
+        let deleted = id_of(&create());
+        client.delete(format!("/games/{}", deleted)).dispatch();
+        let response = client.get(format!("/games/{}", deleted)).dispatch();
+        assert_eq!(response.status(), Status::Gone);
+        assert_eq!(create().status(), Status::Created);
+        assert_eq!(create().status(), Status::TooManyRequests);
+        let response = client
+            .post("/matches")
+            .header(ContentType::JSON)
+            .body(r#"{"best_of": 3, "strategy": "minimax", "sign": "X"}"#)
+            .dispatch();
+        let series = id_of(&response);
+
+        let response = client
+            .post("/admin/reset")
+            .header(Header::new("X-API-Key", "key"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let response = client.get(format!("/games/{}", deleted)).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client.get(format!("/matches/{}", series)).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(create().status(), Status::Created);
+    }
+
+    #[test]
+    fn reset_is_refused_without_the_key_or_when_no_key_is_configured() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        create(&client, r#"{"board": "---------"}"#);
+        let response = client
+            .post("/admin/reset")
+            .header(Header::new("X-API-Key", "wrong"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let client = client_with(|config| config.api_key = None);
+        let response = client
+            .post("/admin/reset")
+            .header(Header::new("X-API-Key", ""))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn creation_rejections_have_distinct_statuses() {
+        let client = client();
+        let cases = [
+            (r#"{"board": "XX"}"#, Status::BadRequest),
+            (r#"{"board": "XX--Z----"}"#, Status::BadRequest),
+            (
+                r#"{"board": "---------", "strategy": "nope"}"#,
+                Status::BadRequest,
+            ),
+            (r#"{"board": "---------", "sign": "Z"}"#, Status::BadRequest),
+            (
+                r#"{"board": "---------", "computer_x_bias": 2.0}"#,
+                Status::BadRequest,
+            ),
+            (r#"{"board": "XXX------"}"#, Status::Conflict),
+            (
+                r#"{"board": "XXXOO----", "resume": true}"#,
+                Status::Conflict,
+            ),
+            (
+                r#"{"board": "XXXOOO---", "resume": true}"#,
+                Status::Conflict,
+            ),
+        ];
+
+        for (board, status) in cases {
+            let response = client
+                .post("/games")
+                .header(ContentType::JSON)
+                .body(board)
+                .dispatch();
+            assert_eq!(response.status(), status, "{}", board);
+            let error = body(response)["error"].as_str().unwrap().to_string();
+            assert!(error.starts_with("Unable to create game: "), "{}", error);
+        }
+    }
+
+    #[test]
+    fn read_token_views_the_game_but_cant_move() {
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "X--------"}"#)
+            .dispatch();
+        let read_token = response
+            .headers()
+            .get_one("X-Read-Token")
+            .unwrap()
+            .to_string();
+        let id = id_of(&response);
+
+        let response = client
+            .get(format!("/games/{}/view?token={}", id, read_token))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(body(response)["id"], id.as_str());
+        let response = client
+            .get(format!("/games/{}/view?token=wrong", id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .put(format!("/games/{}/view?token={}", id, read_token))
+            .header(ContentType::JSON)
+            .body(r#"{"board": "XX-------"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn view_refuses_unknown_games_like_wrong_tokens_and_hides_archived_games() {
+        let client = client();
+        let response = client
+            .get("/games/no-such-game/view?token=wrong")
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "X--------"}"#)
+            .dispatch();
+        let read_token = response
+            .headers()
+            .get_one("X-Read-Token")
+            .unwrap()
+            .to_string();
+        let id = id_of(&response);
+        client.delete(format!("/games/{}?soft=true", id)).dispatch();
+
+        let response = client
+            .get(format!("/games/{}/view?token={}", id, read_token))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn pretty_json_is_indented_and_otherwise_equal() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "X---O----");
+
+        let compact = client
+            .get(format!("/games/{}", id))
+            .dispatch()
+            .into_string()
+            .unwrap();
+        let pretty = client
+            .get(format!("/games/{}?pretty=true", id))
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains("\n  \"board\": \"X---O----\""));
+        assert_eq!(
+            json::from_str::<Value>(&compact).unwrap(),
+            json::from_str::<Value>(&pretty).unwrap()
+        );
+    }
+
+    #[test]
+    fn turn_advances_with_each_move_and_freezes_at_the_end() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["turn"], 3);
+
+        store_board(&client, &id, "X---O----");
+        let game = body(put_board(&client, &id, "XX--O----"));
+        assert_eq!(game["turn"], 5);
+
+        store_board(&client, &id, "XX--OO---");
+        let game = body(put_board(&client, &id, "XXX-OO---"));
+        assert_eq!(game["status"], "X_WON");
+        assert_eq!(game["turn"], 5);
+    }
+
+    #[test]
+    fn creation_is_refused_once_the_store_is_full() {
+        let client = client_with(|config| config.max_games = 3);
+        for _ in 0..3 {
+            create(&client, r#"{"board": "---------"}"#);
+        }
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        let games = body(client.get("/games").dispatch());
+        assert_eq!(games.as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn openapi_document_is_json_listing_the_paths() {
+        let client = client();
+
+        let response = client.get("/openapi.json").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let document = body(response);
+        let paths = document["paths"].as_object().unwrap();
+        for path in [
+            "/games",
+            "/games/{id}",
+            "/games/{id}/ascii",
+            "/games/{id}/svg",
+            "/tournament",
+            "/admin/reset",
+        ] {
+            assert!(paths.contains_key(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn valid_mid_game_board_is_resumed_as_is() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        assert_eq!(game["board"], "X---O----");
+        assert_eq!(game["status"], "RUNNING");
+        let response = put_board(&client, &id, "XX--O----");
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn illegal_mid_game_boards_are_not_resumed() {
+        let client = client();
+        for board in ["XX-------", "OOOX-----", "XXXOOO---"] {
+            let response = client
+                .post("/games")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"board": "{}", "resume": true}}"#, board))
+                .dispatch();
+            assert_eq!(response.status(), Status::Conflict, "{}", board);
+        }
+    }
+
+    #[test]
+    fn owner_record_tallies_the_outcomes_of_their_games() {
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------"}"#)
+            .dispatch();
+        let token = response
+            .headers()
+            .get_one("X-Owner-Token")
+            .unwrap()
+            .to_string();
+        // Resumed boards give the player the side to move, the moves then decide the outcome
+        let games = [
+            (r#"{"board": "XX-OO----", "resume": true}"#, "XXXOO----"),
+            (r#"{"board": "XX-OO----", "resume": true}"#, "XXXOO----"),
+            // The computer's only cell left completes its diagonal
+            (r#"{"board": "XOXOXO-X-", "resume": true}"#, "XOXOXOOX-"),
+            (r#"{"board": "XOXXOOOX-", "resume": true}"#, "XOXXOOOXX"),
+        ];
+        for (game, player_move) in games {
+            let response = client
+                .post("/games")
+                .header(ContentType::JSON)
+                .header(Header::new("X-Owner-Token", token.clone()))
+                .body(game)
+                .dispatch();
+            let id = id_of(&response);
+            assert_eq!(put_board(&client, &id, player_move).status(), Status::Ok);
+        }
+
+        let stats = body(
+            client
+                .get(format!("/games/stats?token={}", token))
+                .dispatch(),
+        );
+
+        assert_eq!(
+            stats,
+            json::json!({"wins": 2, "losses": 1, "draws": 1, "running": 1})
+        );
+        let stats = body(client.get("/games/stats?token=other").dispatch());
+        assert_eq!(
+            stats,
+            json::json!({"wins": 0, "losses": 0, "draws": 0, "running": 0})
+        );
+    }
+
+    #[rocket::async_test]
+    async fn waiter_is_released_by_a_concurrent_move() {
+        capture_logs();
+        let mut config = Config::from_env();
+        config.long_poll_timeout = 5;
+        let client = rocket::local::asynchronous::Client::tracked(build(config))
+            .await
+            .unwrap();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "X---O----", "resume": true}"#)
+            .dispatch()
+            .await;
+        let url: String = response.into_json().await.unwrap();
+        let id = url.rsplit('/').next().unwrap().to_string();
+
+        let wait = client.get(format!("/games/{}/wait", id)).dispatch();
+        let play = async {
+            sleep(Duration::from_millis(100)).await;
+            client
+                .put(format!("/games/{}", id))
+                .header(ContentType::JSON)
+                .body(r#"{"board": "XX--O----"}"#)
+                .dispatch()
+                .await
+        };
+        let (waited, played) = rocket::tokio::join!(wait, play);
+
+        assert_eq!(played.status(), Status::Ok);
+        assert_eq!(waited.status(), Status::Ok);
+        let game: Value = waited.into_json().await.unwrap();
+        assert!(game["board"].as_str().unwrap().starts_with("XX"));
+    }
+
+    #[test]
+    fn waiting_leaves_no_notifier_for_unknown_or_deleted_games() {
+        let client = client();
+        let notifiers = || {
+            let watchers = client.rocket().state::<Watchers>().unwrap();
+            let notifiers = watchers.notifiers.lock().unwrap();
+            notifiers.keys().cloned().collect::<Vec<_>>()
+        };
+
+        let response = client.get("/games/no-such-game/wait").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert!(notifiers().is_empty());
+
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let response = client
+            .get(format!("/games/{}/wait?since=999", id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(notifiers(), vec![id.clone()]);
+        client.delete(format!("/games/{}", id)).dispatch();
+        assert!(notifiers().is_empty());
+    }
+
+    #[test]
+    fn simulate_returns_the_result_but_leaves_the_game_unchanged() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let before = body(client.get(format!("/games/{}", id)).dispatch());
+
+        let response = client
+            .post(format!("/games/{}/simulate", id))
+            .header(ContentType::JSON)
+            .body(r#"{"board": "XX--O----"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let simulated = body(response);
+        assert_eq!(simulated["board"].as_str().unwrap().matches('O').count(), 2);
+        let after = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn grid_matches_the_board_row_wise() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "XO--X--O-");
+
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        assert_eq!(
+            game["grid"],
+            json::json!([["X", "O", "-"], ["-", "X", "-"], ["-", "O", "-"]])
+        );
+        let rows: Vec<String> = game["grid"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                row.as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|cell| cell.as_str().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows.concat(), game["board"].as_str().unwrap());
+    }
+
+    #[test]
+    fn computer_reply_waits_for_the_configured_delay() {
+        assert_eq!(Config::from_env().computer_move_delay, 0);
+        let client = client_with(|config| config.computer_move_delay = 200);
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let started = std::time::Instant::now();
+        let response = put_board(&client, &id, "XX--O----");
+
+        assert_eq!(response.status(), Status::Ok);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        // The computer's opening move on an empty board is delayed as well
+        let started = std::time::Instant::now();
+        create(&client, r#"{"board": "---------", "sign": "O"}"#);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn delay_is_skipped_when_the_computer_doesnt_reply() {
+        let client = client_with(|config| config.computer_move_delay = 1000);
+        let started = std::time::Instant::now();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let analysis = create(&client, r#"{"board": "---------", "analysis": true}"#);
+
+        // Illegal move, move that ends the game and a move in an analysis game
+        assert_eq!(
+            put_board(&client, &id, "XXX-O----").status(),
+            Status::Conflict
+        );
+        let won = create(&client, r#"{"board": "XX--O-O--", "resume": true}"#);
+        assert_eq!(put_board(&client, &won, "XXX-O-O--").status(), Status::Ok);
+        assert_eq!(
+            put_board(&client, &analysis, "X--------").status(),
+            Status::Ok
+        );
+
+        assert!(started.elapsed() < Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn id_listing_has_the_created_games() {
+        let client = client();
+        let mut created: Vec<String> = (0..3)
+            .map(|_| create(&client, r#"{"board": "---------"}"#))
+            .collect();
+
+        let mut ids: Vec<String> = client.get("/games/ids").dispatch().into_json().unwrap();
+
+        created.sort();
+        ids.sort();
+        assert_eq!(ids, created);
+    }
+
+    #[test]
+    fn put_with_a_stale_etag_is_rejected() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let response = client.get(format!("/games/{}", id)).dispatch();
+        let stale = response.headers().get_one("ETag").unwrap().to_string();
+        let response = client
+            .put(format!("/games/{}", id))
+            .header(ContentType::JSON)
+            .header(Header::new("If-Match", stale.clone()))
+            .body(r#"{"board": "XX--O----"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let board = body(response)["board"].clone();
+        let empty = board.as_str().unwrap().find('-').unwrap();
+        let mut next = board.as_str().unwrap().to_string();
+        next.replace_range(empty..empty + 1, "X");
+
+        let response = client
+            .put(format!("/games/{}", id))
+            .header(ContentType::JSON)
+            .header(Header::new("If-Match", stale))
+            .body(format!(r#"{{"board": "{}"}}"#, next))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::PreconditionFailed);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], board);
+    }
+
+    #[test]
+    fn rejected_moves_are_logged() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let response = put_board(&client, &id, "XXX-O----");
+
+        assert_eq!(response.status(), Status::Conflict);
+        let lines = logged(&format!("Rejected move: game_id={} ", id));
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains(r#"submitted="XXX-O----" stored="X---O----""#));
+    }
+
+    #[test]
+    fn deleted_game_is_gone_and_unknown_game_is_not_found() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+
+        let response = client.delete(format!("/games/{}", id)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get(format!("/games/{}", id)).dispatch();
+        assert_eq!(response.status(), Status::Gone);
+        let response = client.get("/games/unknown").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    /// Plays a tournament with the JSON payload
+    fn tournament<'c>(client: &'c Client, payload: &str) -> LocalResponse<'c> {
+        client
+            .post("/tournament")
+            .header(ContentType::JSON)
+            .body(payload)
+            .dispatch()
+    }
+
+    #[test]
+    fn tournament_totals_sum_up_to_the_games_played() {
+        let client = client();
+
+        let result = body(tournament(
+            &client,
+            r#"{"games": 10, "x": "random", "o": "block"}"#,
+        ));
+
+        assert_eq!(result["games"], 10);
+        let total = ["x_wins", "o_wins", "draws"]
+            .iter()
+            .map(|field| result[field].as_u64().unwrap())
+            .sum::<u64>();
+        assert_eq!(total, 10);
+    }
+
+    #[test]
+    fn tournament_between_minimax_players_is_drawn_and_capped() {
+        let client = client();
+
+        let result = body(tournament(
+            &client,
+            r#"{"games": 1000, "x": "minimax", "o": "minimax"}"#,
+        ));
+
+        assert_eq!(
+            result,
+            json::json!({"games": 100, "x_wins": 0, "o_wins": 0, "draws": 100})
+        );
+    }
+
+    #[test]
+    fn tournament_with_an_unknown_strategy_is_rejected() {
+        let client = client();
+
+        let response = tournament(&client, r#"{"games": 3, "x": "random", "o": "nope"}"#);
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn yaml_is_returned_when_accepted_and_parses_back() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "X---O----");
+        let json_game = body(client.get(format!("/games/{}", id)).dispatch());
+
+        let response = client
+            .get(format!("/games/{}", id))
+            .header(Header::new("Accept", "application/yaml"))
+            .dispatch();
+
+        assert_eq!(
+            response.content_type(),
+            Some(ContentType::new("application", "yaml"))
+        );
+        let yaml_game: Value = serde_yaml::from_str(&response.into_string().unwrap()).unwrap();
+        assert_eq!(yaml_game, json_game);
+    }
+
+    /// Sets the board of a game through the admin endpoint
+    fn set_board<'c>(client: &'c Client, id: &str, board: &str) -> LocalResponse<'c> {
+        client
+            .put(format!("/games/{}/board", id))
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", "key"))
+            .body(format!(r#"{{"board": "{}"}}"#, board))
+            .dispatch()
+    }
+
+    #[test]
+    fn setting_a_won_board_updates_the_status_without_a_reply() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "---------");
+
+        let response = set_board(&client, &id, "XXXOO----");
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["board"], "XXXOO----");
+        assert_eq!(game["status"], "X_WON");
+        assert_eq!(game["game_over"], true);
+    }
+
+    #[test]
+    fn setting_an_unreachable_board_is_rejected() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "---------");
+
+        for board in ["XXXOOO---", "XXXOOOX--", "XX-------"] {
+            assert_eq!(set_board(&client, &id, board).status(), Status::Conflict);
+        }
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], "---------");
+    }
+
+    #[test]
+    fn setting_a_board_only_accepts_json() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        let before = body(client.get(format!("/games/{}", id)).dispatch());
+
+        let response = client
+            .put(format!("/games/{}/board", id))
+            .header(ContentType::Plain)
+            .header(Header::new("X-API-Key", "key"))
+            .body(r#"{"board": "XO-------"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], before["board"]);
+    }
+
+    #[test]
+    fn board_with_a_completed_line_is_not_created() {
+        let client = client();
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "XXXOO----", "resume": true}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Conflict);
+        assert_eq!(
+            body(response)["error"],
+            "Unable to create game: board already contains a win"
+        );
+        assert_eq!(body(client.get("/games").dispatch()), json::json!([]));
+    }
+
+    #[test]
+    fn clone_is_independent_of_its_source() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let response = client.post(format!("/games/{}/clone", id)).dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let copy = id_of(&response);
+        assert_ne!(copy, id);
+
+        put_board(&client, &copy, "XX--O----");
+        put_board(&client, &id, "X---O---X");
+
+        let source = body(client.get(format!("/games/{}", id)).dispatch());
+        let clone = body(client.get(format!("/games/{}", copy)).dispatch());
+        let source = source["board"].as_str().unwrap().as_bytes();
+        let clone = clone["board"].as_str().unwrap().as_bytes();
+        assert_eq!(source[8], b'X');
+        assert_ne!(source[1], b'X');
+        assert_eq!(clone[1], b'X');
+        assert_ne!(clone[8], b'X');
+    }
+
+    #[test]
+    fn index_describes_the_api_as_json() {
+        let client = client();
+
+        let response = client.get("/").dispatch();
+
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let info = body(response);
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(info["name"], env!("CARGO_PKG_NAME"));
+        let endpoints = info["endpoints"].as_array().unwrap();
+        assert!(endpoints.contains(&json::json!("POST /games")));
+        assert!(endpoints.contains(&json::json!("GET /games/<id>/ascii")));
+    }
+
+    #[rocket::async_test]
+    async fn idle_game_is_forfeited_after_the_move_timeout() {
+        capture_logs();
+        let mut config = Config::from_env();
+        config.move_timeout = 1;
+        let client = rocket::local::asynchronous::Client::tracked(build(config))
+            .await
+            .unwrap();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "X---O----", "resume": true}"#)
+            .dispatch()
+            .await;
+        let url: String = response.into_json().await.unwrap();
+        let id = url.rsplit('/').next().unwrap().to_string();
+
+        sleep(Duration::from_millis(2500)).await;
+
+        let game: Value = client
+            .get(format!("/games/{}", id))
+            .dispatch()
+            .await
+            .into_json()
+            .await
+            .unwrap();
+        assert_eq!(game["status"], "O_WON");
+        assert_eq!(game["game_over"], true);
+        assert_eq!(game["ended_by"], "TIMEOUT");
+        let forfeited = format!("Game {} forfeited after the move timeout", id);
+        assert_eq!(logged(&forfeited).len(), 1);
+    }
+
+    #[test]
+    fn forced_win_evaluates_positive_for_the_side_to_move() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "XX-OO----");
+
+        let evaluation = body(client.get(format!("/games/{}/eval", id)).dispatch());
+
+        assert_eq!(evaluation["to_move"], "X");
+        assert!(evaluation["score"].as_i64().unwrap() > 0);
+        assert_eq!(evaluation["best_move"], 2);
+    }
+
+    #[test]
+    fn finished_game_has_no_evaluation() {
+        let client = client();
+        let id = create(&client, r#"{"board": "XX-OO----", "resume": true}"#);
+        put_board(&client, &id, "XXXOO----");
+
+        let response = client.get(format!("/games/{}/eval", id)).dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn archived_game_is_hidden_but_retrievable() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+        let kept = create(&client, r#"{"board": "---------"}"#);
+
+        let response = client.delete(format!("/games/{}?soft=true", id)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(body(response)["archived"], true);
+
+        let ids: Vec<String> = client.get("/games/ids").dispatch().into_json().unwrap();
+        assert_eq!(ids, vec![kept]);
+        let games = body(client.get("/games").dispatch());
+        assert_eq!(games.as_array().unwrap().len(), 1);
+        let response = client.get(format!("/games/{}", id)).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .get(format!("/games/{}?include_archived=true", id))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(body(response)["id"], id.as_str());
+    }
+
+    #[test]
+    fn single_threat_for_x_is_reported() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X--------"}"#);
+        store_board(&client, &id, "XX--O----");
+
+        let threats = body(client.get(format!("/games/{}/threats", id)).dispatch());
+
+        assert_eq!(threats, json::json!({"x": [2], "o": []}));
+    }
+
+    #[test]
+    fn cors_reflects_only_allowed_origins_and_always_varies_by_origin() {
+        let client = client_with(|config| config.cors_origins = vec!["https://a.example".into()]);
+
+        let allowed = client
+            .get("/")
+            .header(Header::new("Origin", "https://a.example"))
+            .dispatch();
+        assert_eq!(
+            allowed.headers().get_one("Access-Control-Allow-Origin"),
+            Some("https://a.example")
+        );
+        assert_eq!(allowed.headers().get_one("Vary"), Some("Origin"));
+
+        let disallowed = client
+            .get("/")
+            .header(Header::new("Origin", "https://b.example"))
+            .dispatch();
+        assert_eq!(
+            disallowed.headers().get_one("Access-Control-Allow-Origin"),
+            None
+        );
+        assert_eq!(disallowed.headers().get_one("Vary"), Some("Origin"));
+
+        let missing = client.get("/").dispatch();
+        assert_eq!(
+            missing.headers().get_one("Access-Control-Allow-Origin"),
+            None
+        );
+        assert_eq!(missing.headers().get_one("Vary"), Some("Origin"));
+    }
+
+    #[test]
+    fn patch_replacing_one_cell_makes_the_move() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let response = client
+            .patch(format!("/games/{}", id))
+            .header(ContentType::new("application", "json-patch+json"))
+            .body(r#"[{"op": "replace", "path": "/grid/0/1", "value": "X"}]"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let board = body(response)["board"].as_str().unwrap().to_string();
+        assert!(board.starts_with("XX"));
+        assert_eq!(board.matches('O').count(), 2);
+    }
+
+    #[test]
+    fn spectator_count_rises_with_a_stream_and_falls_after_disconnect() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+        let spectators =
+            || body(client.get(format!("/games/{}", id)).dispatch())["spectators"].clone();
+        assert_eq!(spectators(), 0);
+
+        let stream = client.get(format!("/games/{}/events", id)).dispatch();
+        assert_eq!(stream.status(), Status::Ok);
+        assert_eq!(spectators(), 1);
+        assert_eq!(body(client.get("/games").dispatch())[0]["spectators"], 1);
+
+        drop(stream);
+        assert_eq!(spectators(), 0);
+    }
+
+    #[test]
+    fn spectator_count_is_not_read_from_imported_games() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let mut exported = body(
+            client
+                .get("/admin/export")
+                .header(Header::new("X-API-Key", "key"))
+                .dispatch(),
+        );
+        exported[0]["spectators"] = json::json!(7);
+
+        let response = client
+            .post("/admin/import")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", "key"))
+            .body(exported.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["spectators"], 0);
+        assert_eq!(body(client.get("/games").dispatch())[0]["spectators"], 0);
+    }
+
+    #[test]
+    fn undo_computer_clears_only_the_computers_cell() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+        let after_move = body(put_board(&client, &id, "XX--O----"));
+        let replied = after_move["board"].as_str().unwrap();
+        assert_eq!(replied.matches('O').count(), 2);
+
+        let response = client
+            .post(format!("/games/{}/undo-computer", id))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(body(response)["board"], "XX--O----");
+    }
+
+    #[test]
+    fn finished_game_transcript_is_logged() {
+        let client = client_with(|config| config.transcript_log = true);
+        let id = create(
+            &client,
+            r#"{"board": "XX-OO----", "resume": true, "sign": "X"}"#,
+        );
+
+        put_board(&client, &id, "XXXOO----");
+
+        let transcripts = logged(&format!(r#""id":"{}""#, id));
+        assert_eq!(transcripts.len(), 1);
+        assert!(transcripts[0].starts_with("Game transcript: "));
+        assert!(transcripts[0].contains(r#""status":"X_WON""#));
+        assert!(transcripts[0].contains(r#""ended_by":"PLAYER""#));
+    }
+
+    #[test]
+    fn invalid_boards_are_rejected_with_the_specific_reason() {
+        let client = client();
+        let rejection = |board: &str| {
+            let response = client
+                .post("/games")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"board": "{}"}}"#, board))
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+            body(response)["error"].clone()
+        };
+
+        assert_eq!(
+            rejection("----"),
+            "Unable to create game: board must be exactly 9 characters, got 4"
+        );
+        assert_eq!(
+            rejection("----Z----"),
+            "Unable to create game: board contains invalid character 'Z'"
+        );
+    }
+
+    #[test]
+    fn analysis_game_alternates_marks_without_computer_replies() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+
+        for board in ["----X----", "O---X----", "O-X-X----", "OOX-X----"] {
+            let game = body(put_board(&client, &id, board));
+            assert_eq!(game["board"], board);
+            assert_eq!(game["status"], "RUNNING");
+        }
+        // Placing O again breaks the alternation
+        assert_ne!(put_board(&client, &id, "OOXOX----").status(), Status::Ok);
+
+        let game = body(put_board(&client, &id, "OOX-X-X--"));
+        assert_eq!(game["board"], "OOX-X-X--");
+        assert_eq!(game["status"], "X_WON");
+    }
+
+    #[test]
+    fn tree_root_children_are_the_legal_moves() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+
+        let root = body(client.get(format!("/games/{}/tree?depth=1", id)).dispatch());
+
+        assert_eq!(root["board"], "X---O----");
+        let children = root["children"].as_array().unwrap();
+        let indexes: Vec<u64> = children
+            .iter()
+            .map(|child| child["index"].as_u64().unwrap())
+            .collect();
+        assert_eq!(indexes, [1, 2, 3, 5, 6, 7, 8]);
+        for child in children {
+            let index = child["index"].as_u64().unwrap() as usize;
+            let mut board: Vec<char> = "X---O----".chars().collect();
+            board[index] = 'X';
+            assert_eq!(child["board"], board.into_iter().collect::<String>());
+            assert_eq!(child["children"], json::json!([]));
+        }
+    }
+
+    #[test]
+    fn tree_of_a_finished_game_is_rejected() {
+        let client = client();
+        let id = create(&client, r#"{"board": "XX-OO----", "analysis": true}"#);
+        put_board(&client, &id, "XXXOO----");
+
+        let response = client.get(format!("/games/{}/tree", id)).dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn same_seed_and_moves_give_the_same_computer_replies() {
+        let client = client();
+        let play = || {
+            let id = create(&client, r#"{"board": "X--------", "seed": 42}"#);
+            let mut replies = Vec::new();
+            for index in 0..9 {
+                let game = body(client.get(format!("/games/{}", id)).dispatch());
+                if game["status"] != "RUNNING" {
+                    break;
+                }
+                let board = game["board"].as_str().unwrap();
+                if board.as_bytes()[index] != b'-' {
+                    continue;
+                }
+                let mut cells: Vec<char> = board.chars().collect();
+                cells[index] = 'X';
+                let game = body(put_board(
+                    &client,
+                    &id,
+                    &cells.into_iter().collect::<String>(),
+                ));
+                replies.push(game["board"].clone());
+            }
+            replies
+        };
+
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn options_lists_the_allowed_methods_of_each_resource() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+
+        let games = client.options("/games").dispatch();
+        assert_eq!(games.status(), Status::NoContent);
+        assert_eq!(games.headers().get_one("Allow"), Some("GET, POST, OPTIONS"));
+
+        let game = client.options(format!("/games/{}", id)).dispatch();
+        assert_eq!(game.status(), Status::NoContent);
+        assert_eq!(
+            game.headers().get_one("Allow"),
+            Some("GET, PUT, PATCH, DELETE, OPTIONS")
+        );
+    }
+
+    #[test]
+    fn thinking_time_accumulates_for_the_side_that_moved() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+
+        std::thread::sleep(Duration::from_millis(80));
+        let game = body(put_board(&client, &id, "----X----"));
+        let x = game["time_used"]["x"].as_u64().unwrap();
+        assert!(x >= 80);
+        assert_eq!(game["time_used"]["o"], 0);
+
+        std::thread::sleep(Duration::from_millis(40));
+        let game = body(put_board(&client, &id, "O---X----"));
+        // Only O was thinking since X moved
+        assert_eq!(game["time_used"]["x"], x);
+        assert!(game["time_used"]["o"].as_u64().unwrap() >= 40);
+    }
+
+    #[test]
+    fn player_filling_the_last_cell_draws_without_a_computer_reply() {
+        let client = client();
+        let id = create(
+            &client,
+            r#"{"board": "XOXXOOOX-", "resume": true, "sign": "X"}"#,
+        );
+
+        let response = put_board(&client, &id, "XOXXOOOXX");
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Computer-Move"), None);
+        let game = body(response);
+        assert_eq!(game["board"], "XOXXOOOXX");
+        assert_eq!(game["status"], "DRAW");
+        assert_eq!(game["ended_by"], "PLAYER");
+    }
+
+    #[test]
+    fn move_sequence_is_played_until_the_game_ends() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+
+        let response = client
+            .post(format!("/games/{}/moves", id))
+            .header(ContentType::JSON)
+            .body("[4, 0, 2, 1, 6]")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let result = body(response);
+        assert_eq!(result["applied"], 5);
+        assert_eq!(result["error"], Value::Null);
+        assert_eq!(result["game"]["board"], "OOX-X-X--");
+        assert_eq!(result["game"]["status"], "X_WON");
+    }
+
+    #[test]
+    fn move_sequence_stops_at_an_illegal_move() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "resume": true}"#);
+
+        let response = client
+            .post(format!("/games/{}/moves", id))
+            .header(ContentType::JSON)
+            .body("[1, 1, 2]")
+            .dispatch();
+
+        let result = body(response);
+        assert_eq!(result["applied"], 1);
+        assert!(result["error"].is_string());
+        let board = result["game"]["board"].as_str().unwrap();
+        assert_eq!(board.as_bytes()[1], b'X');
+        assert_eq!(board.matches('X').count(), 2);
+        assert_eq!(board.matches('O').count(), 2);
+    }
+
+    #[test]
+    fn store_stats_count_the_created_games_and_players() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let stats = || {
+            body(
+                client
+                    .get("/admin/stats")
+                    .header(Header::new("X-API-Key", "key"))
+                    .dispatch(),
+            )
+        };
+        let empty = stats();
+        assert_eq!(empty["games"], 0);
+        assert_eq!(empty["players"], 0);
+
+        create(&client, r#"{"board": "---------"}"#);
+        create(&client, r#"{"board": "---------"}"#);
+        create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+
+        let stats = stats();
+        assert_eq!(stats["games"], 3);
+        assert_eq!(stats["archived_games"], 0);
+        assert_eq!(stats["players"], 3);
+        assert!(stats["approximate_bytes"].as_u64() > empty["approximate_bytes"].as_u64());
+        let refused = client.get("/admin/stats").dispatch();
+        assert_eq!(refused.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn swap_gives_the_player_the_opening_sign_for_later_moves() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------"}"#);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        let opening = game["history"][0]["sign"].as_str().unwrap().to_string();
+        let reply = if opening == "X" { "O" } else { "X" };
+
+        let response = client.post(format!("/games/{}/swap", id)).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["history"][0]["sign"], opening.as_str());
+        assert_eq!(game["history"][0]["by"], "PLAYER");
+        assert_eq!(game["history"][1]["sign"], reply);
+        assert_eq!(game["history"][1]["by"], "COMPUTER");
+
+        // The player now moves with the opening sign
+        let board = game["board"].as_str().unwrap();
+        let empty = board.find('-').unwrap();
+        let with = |sign: &str| format!("{}{}{}", &board[..empty], sign, &board[empty + 1..]);
+        assert_eq!(
+            put_board(&client, &id, &with(reply)).status(),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            put_board(&client, &id, &with(&opening)).status(),
+            Status::Ok
+        );
+
+        let again = client.post(format!("/games/{}/swap", id)).dispatch();
+        assert_eq!(again.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn new_game_header_tells_who_opened_the_game() {
+        let client = client();
+        let created = |payload: &str| {
+            let response = client
+                .post("/games")
+                .header(ContentType::JSON)
+                .body(payload)
+                .dispatch();
+            assert_eq!(response.status(), Status::Created);
+            response.headers().get_one("X-New-Game").map(String::from)
+        };
+
+        assert_eq!(
+            created(r#"{"board": "---------", "sign": "O"}"#).as_deref(),
+            Some("true")
+        );
+        assert_eq!(
+            created(r#"{"board": "----X----"}"#).as_deref(),
+            Some("false")
+        );
+    }
+
+    #[test]
+    fn created_game_body_tells_who_opened_the_game() {
+        let client = client();
+        let created = |payload: &str| {
+            let response = client
+                .post("/games?return=game")
+                .header(ContentType::JSON)
+                .body(payload)
+                .dispatch();
+            assert_eq!(response.status(), Status::Created);
+            body(response)
+        };
+
+        let opened = created(r#"{"board": "---------", "sign": "O"}"#);
+        assert_eq!(opened["is_new"], true);
+        let answered = created(r#"{"board": "----X----"}"#);
+        assert_eq!(answered["is_new"], false);
+
+        // Only reported on creation
+        let id = answered["id"].as_str().unwrap();
+        let stored = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(stored.get("is_new"), None);
+    }
+
+    #[test]
+    fn win_type_classifies_row_and_diagonal_wins() {
+        let client = client();
+        let row = create(&client, r#"{"board": "XX-OO----", "analysis": true}"#);
+        put_board(&client, &row, "XXXOO----");
+        let diagonal = create(&client, r#"{"board": "OOX-X----", "analysis": true}"#);
+        put_board(&client, &diagonal, "OOX-X-X--");
+        let running = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+
+        let win_type = |id: &str| client.get(format!("/games/{}/win-type", id)).dispatch();
+
+        assert_eq!(
+            body(win_type(&row)),
+            json::json!({"sign": "X", "kind": "ROW", "index": 0, "cells": [0, 1, 2]})
+        );
+        assert_eq!(
+            body(win_type(&diagonal)),
+            json::json!({"sign": "X", "kind": "DIAGONAL", "index": 1, "cells": [2, 4, 6]})
+        );
+        assert_eq!(win_type(&running).status(), Status::NoContent);
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_trimmed_unless_boards_are_strict() {
+        let payload = r#"{"board": "X---O----\n", "resume": true}"#;
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(payload)
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let game = body(
+            client
+                .get(format!("/games/{}", id_of(&response)))
+                .dispatch(),
+        );
+        assert_eq!(game["board"], "X---O----");
+
+        let client = client_with(|config| config.strict_boards = true);
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(payload)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(
+            body(response)["error"],
+            "Unable to create game: board must be exactly 9 characters, got 10"
+        );
+    }
+
+    #[test]
+    fn center_opening_is_a_draw_with_perfect_play() {
+        let client = client();
+        let center = create(&client, r#"{"board": "----X----", "analysis": true}"#);
+        let forced = create(&client, r#"{"board": "XX-OO----", "analysis": true}"#);
+
+        let outcome = |id: &str| body(client.get(format!("/games/{}/outcome", id)).dispatch());
+
+        assert_eq!(
+            outcome(&center),
+            json::json!({"to_move": "O", "outcome": "DRAW"})
+        );
+        assert_eq!(
+            outcome(&forced),
+            json::json!({"to_move": "X", "outcome": "X_WON"})
+        );
+        put_board(&client, &forced, "XXXOO----");
+        let finished = client.get(format!("/games/{}/outcome", forced)).dispatch();
+        assert_eq!(finished.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn custom_marks_play_and_win() {
+        let client = client_with(|config| config.marks = ('A', 'B'));
+        let id = create(
+            &client,
+            r#"{"board": "AA-BB----", "resume": true, "sign": "A"}"#,
+        );
+
+        let response = put_board(&client, &id, "AAABB----");
+
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["board"], "AAABB----");
+        assert_eq!(game["status"], "X_WON");
+        assert_eq!(game["ended_by"], "PLAYER");
+        // The internal marks are not accepted in place of the configured ones
+        let other = create(&client, r#"{"board": "---------", "sign": "A"}"#);
+        assert_ne!(put_board(&client, &other, "----X----").status(), Status::Ok);
+    }
+
+    #[test]
+    fn games_are_grouped_by_status() {
+        let client = client();
+        let running = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+        let x_won = create(&client, r#"{"board": "XX-OO----", "analysis": true}"#);
+        put_board(&client, &x_won, "XXXOO----");
+        let o_won = create(&client, r#"{"board": "XX-OO-X--", "analysis": true}"#);
+        put_board(&client, &o_won, "XX-OOOX--");
+        let draw = create(&client, r#"{"board": "XOXXOOOX-", "analysis": true}"#);
+        put_board(&client, &draw, "XOXXOOOXX");
+
+        let grouped = body(client.get("/games/grouped").dispatch());
+
+        assert_eq!(
+            grouped,
+            json::json!({
+                "RUNNING": [running],
+                "X_WON": [x_won],
+                "O_WON": [o_won],
+                "DRAW": [draw],
+            })
+        );
+    }
+
+    #[test]
+    fn game_url_is_exactly_the_base_and_the_game_path() {
+        assert_eq!(
+            game_url("4f0e5c1a-7b7d-4c8e-9a57-2b3c4d5e6f70")
+                .unwrap()
+                .as_str(),
+            "http://127.0.0.1:8000/games/4f0e5c1a-7b7d-4c8e-9a57-2b3c4d5e6f70"
+        );
+    }
+
+    #[test]
+    fn created_game_location_points_at_the_game() {
+        let client = client();
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Created);
+        let location = response.headers().get_one("Location").unwrap().to_string();
+        assert_eq!(body(response), location.as_str());
+        let path = location.strip_prefix("http://127.0.0.1:8000").unwrap();
+        let game = client.get(path).dispatch();
+        assert_eq!(game.status(), Status::Ok);
+        assert_eq!(
+            format!("/games/{}", body(game)["id"].as_str().unwrap()),
+            path
+        );
+    }
+
+    #[test]
+    fn computer_moves_with_the_strategy_chosen_by_name() {
+        let client = client();
+        for strategy in ["random", "block", "minimax"] {
+            let id = create(
+                &client,
+                &format!(
+                    r#"{{"board": "X---O----", "resume": true, "sign": "X", "strategy": "{}"}}"#,
+                    strategy
+                ),
+            );
+
+            let game = body(put_board(&client, &id, "XX--O----"));
+
+            assert_eq!(game["strategy"], strategy);
+            if strategy != "random" {
+                assert_eq!(game["board"], "XXO-O----");
+            }
+        }
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------", "strategy": "unknown"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn notation_lists_the_moves_in_order() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+        let notation = |id: &str| client.get(format!("/games/{}/notation", id)).dispatch();
+        assert_eq!(notation(&id).into_string().unwrap(), "");
+
+        for board in ["----X----", "O---X----", "O---X---X"] {
+            put_board(&client, &id, board);
+        }
+
+        assert_eq!(notation(&id).into_string().unwrap(), "X:4 O:0 X:8");
+        assert_eq!(notation("unknown").status(), Status::NotFound);
+    }
+
+    #[test]
+    fn imported_notation_recreates_the_board_and_history() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+        put_board(&client, &id, "----X----");
+        let original = body(client.get(format!("/games/{}", id)).dispatch());
+        let notation = client
+            .get(format!("/games/{}/notation", id))
+            .dispatch()
+            .into_string()
+            .unwrap();
+
+        let response = client.post("/games/import").body(notation).dispatch();
+
+        assert_eq!(response.status(), Status::Created);
+        let imported = body(response);
+        assert_ne!(imported["id"], original["id"]);
+        assert_eq!(imported["board"], original["board"]);
+        let moves = |game: &Value| -> Vec<(Value, Value)> {
+            game["history"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|past| (past["sign"].clone(), past["index"].clone()))
+                .collect()
+        };
+        assert_eq!(moves(&imported), moves(&original));
+
+        let illegal = client.post("/games/import").body("X:4 O:4").dispatch();
+        assert_eq!(illegal.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn finished_games_are_hidden_only_when_configured() {
+        let ids = |client: &Client, uri: &str| -> Vec<String> {
+            let mut ids: Vec<String> = body(client.get(uri.to_string()).dispatch())
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|game| game["id"].as_str().unwrap().to_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        let setup = |client: &Client| {
+            let running = create(client, r#"{"board": "X---O----", "analysis": true}"#);
+            let finished = create(client, r#"{"board": "XX-OO----", "analysis": true}"#);
+            put_board(client, &finished, "XXXOO----");
+            let mut both = vec![running.clone(), finished];
+            both.sort();
+            (vec![running], both)
+        };
+
+        let client = client();
+        let (_, both) = setup(&client);
+        assert_eq!(ids(&client, "/games"), both);
+
+        let client = client_with(|config| config.hide_finished_games = true);
+        let (running, both) = setup(&client);
+        assert_eq!(ids(&client, "/games"), running);
+        assert_eq!(ids(&client, "/games?include_finished=true"), both);
+    }
+
+    #[test]
+    fn fork_cell_is_reported_for_the_side_that_has_it() {
+        let client = client();
+        // X at 8 threatens both the diagonal through 4 and the column through 2
+        let id = create(&client, r#"{"board": "XO-O-X---", "analysis": true}"#);
+
+        let forks = body(client.get(format!("/games/{}/forks", id)).dispatch());
+
+        assert_eq!(forks, json::json!({"x": [8], "o": []}));
+    }
+
+    #[test]
+    fn chosen_sign_decides_who_opens_an_empty_board() {
+        let client = client();
+        let board =
+            |id: &str| body(client.get(format!("/games/{}", id)).dispatch())["board"].clone();
+
+        let x = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+        assert_eq!(board(&x), "---------");
+        assert_eq!(put_board(&client, &x, "----X----").status(), Status::Ok);
+
+        let o = create(&client, r#"{"board": "---------", "sign": "O"}"#);
+        let opened = board(&o);
+        assert_eq!(opened.as_str().unwrap().matches('X').count(), 1);
+        assert_eq!(opened.as_str().unwrap().matches('-').count(), 8);
+
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------", "sign": "Z"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn envelope_wraps_the_raw_payload_with_metadata() {
+        let threats = |client: &Client| {
+            let id = create(client, r#"{"board": "XX--O----", "analysis": true}"#);
+            body(client.get(format!("/games/{}/threats", id)).dispatch())
+        };
+        let raw = threats(&client());
+
+        let client = client_with(|config| config.response_envelope = true);
+        let enveloped = threats(&client);
+
+        assert_eq!(enveloped["data"], raw);
+        assert!(Uuid::parse_str(enveloped["meta"]["request_id"].as_str().unwrap()).is_ok());
+        assert!(enveloped["meta"]["timestamp"].as_u64().unwrap() > 0);
+        // Errors are never enveloped
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "----"}"#)
+            .dispatch();
+        assert_eq!(
+            body(response),
+            json::json!({"error": "Unable to create game: board must be exactly 9 characters, got 4"})
+        );
+    }
+
+    #[test]
+    fn tokens_follow_the_games() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let tokens = client.rocket().state::<TokenList>().unwrap();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------", "pvp": true}"#)
+            .dispatch();
+        let deleted = id_of(&response);
+        let read_token = response
+            .headers()
+            .get_one("X-Read-Token")
+            .unwrap()
+            .to_string();
+        let kept = create(&client, r#"{"board": "---------"}"#);
+
+        client.delete(format!("/games/{}", deleted)).dispatch();
+
+        assert!(!tokens.can_read(&deleted, &read_token));
+        assert_eq!(tokens.move_tokens(&deleted), None);
+        assert!(tokens.owner_tokens.lock().unwrap().get(&deleted).is_none());
+
+        // Entries left behind without a game are collected
+        tokens.issue_read_token(&deleted);
+        tokens.issue_move_tokens(&deleted);
+        let response = client
+            .post("/admin/gc")
+            .header(Header::new("X-API-Key", "key"))
+            .dispatch();
+        assert_eq!(
+            body(response),
+            json::json!({"removed": 0, "removed_tokens": 2})
+        );
+        assert_eq!(tokens.move_tokens(&deleted), None);
+        assert!(tokens.read_tokens.lock().unwrap().contains_key(&kept));
+        assert!(tokens.owner_tokens.lock().unwrap().contains_key(&kept));
+    }
+
+    #[test]
+    fn player_entries_follow_the_games() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let players = || {
+            client
+                .rocket()
+                .state::<PlayerList>()
+                .unwrap()
+                .player_map
+                .lock()
+                .unwrap()
+                .len()
+        };
+        let kept = create(&client, r#"{"board": "---------"}"#);
+        let deleted = create(&client, r#"{"board": "---------"}"#);
+        assert_eq!(players(), 2);
+
+        let response = client.delete(format!("/games/{}", deleted)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(players(), 1);
+
+        // An entry left behind without a game is collected
+        client
+            .rocket()
+            .state::<PlayerList>()
+            .unwrap()
+            .player_map
+            .lock()
+            .unwrap()
+            .insert(deleted, Sign::X);
+        let gc = |client: &Client| {
+            body(
+                client
+                    .post("/admin/gc")
+                    .header(Header::new("X-API-Key", "key"))
+                    .dispatch(),
+            )
+        };
+        assert_eq!(
+            gc(&client),
+            json::json!({"removed": 1, "removed_tokens": 0})
+        );
+        assert_eq!(
+            gc(&client),
+            json::json!({"removed": 0, "removed_tokens": 0})
+        );
+        let players = client
+            .rocket()
+            .state::<PlayerList>()
+            .unwrap()
+            .player_map
+            .lock()
+            .unwrap();
+        assert!(players.contains_key(&kept));
+        assert_eq!(players.len(), 1);
+    }
+
+    #[test]
+    fn move_with_a_mismatching_body_id_is_rejected() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+        let other = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+        let put = |body_id: &str| {
+            client
+                .put(format!("/games/{}", id))
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"id": "{}", "board": "----X----"}}"#, body_id))
+                .dispatch()
+        };
+
+        assert_eq!(put(&other).status(), Status::BadRequest);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], "---------");
+        assert_eq!(put(&id).status(), Status::Ok);
+    }
+
+    #[test]
+    fn owner_cap_counts_only_that_owners_running_games() {
+        let client = client_with(|config| config.max_games_per_owner = 2);
+        let create_as = |owner: &str, payload: &str| {
+            client
+                .post("/games")
+                .header(ContentType::JSON)
+                .header(Header::new("X-Owner-Token", owner.to_string()))
+                .body(payload)
+                .dispatch()
+        };
+        let empty = r#"{"board": "---------"}"#;
+
+        let finishing = create_as("alice", r#"{"board": "XX-OO----", "analysis": true}"#);
+        assert_eq!(finishing.status(), Status::Created);
+        assert_eq!(create_as("alice", empty).status(), Status::Created);
+        assert_eq!(create_as("alice", empty).status(), Status::TooManyRequests);
+        assert_eq!(create_as("bob", empty).status(), Status::Created);
+
+        // Finished games don't count toward the cap
+        put_board(&client, &id_of(&finishing), "XXXOO----");
+        assert_eq!(create_as("alice", empty).status(), Status::Created);
+    }
+
+    #[test]
+    fn status_can_be_set_to_each_value_without_touching_the_board() {
+        let client = client_with(|config| config.api_key = Some(String::from("key")));
+        let id = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+        let set = |status: &str| {
+            client
+                .put(format!("/games/{}/status", id))
+                .header(ContentType::JSON)
+                .header(Header::new("X-API-Key", "key"))
+                .body(format!(r#"{{"status": "{}"}}"#, status))
+                .dispatch()
+        };
+
+        for status in ["X_WON", "O_WON", "DRAW", "RUNNING"] {
+            let response = set(status);
+            assert_eq!(response.status(), Status::Ok);
+            let game = body(response);
+            assert_eq!(game["status"], status);
+            assert_eq!(game["board"], "X---O----");
+        }
+        assert_eq!(set("LOST").status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn board_at_a_ply_is_rebuilt_from_the_history() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+        for board in ["----X----", "O---X----", "O---X---X"] {
+            put_board(&client, &id, board);
+        }
+        let at = |ply: &str| client.get(format!("/games/{}/at/{}", id, ply)).dispatch();
+
+        assert_eq!(body(at("0"))["board"], "---------");
+        assert_eq!(body(at("2"))["board"], "O---X----");
+        assert_eq!(body(at("3"))["board"], "O---X---X");
+        assert_eq!(at("4").status(), Status::BadRequest);
+        let unknown = client.get("/games/unknown/at/1").dispatch();
+        assert_eq!(unknown.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn move_changing_two_cells_is_a_conflict() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+
+        let response = put_board(&client, &id, "X---X----");
+
+        assert_eq!(response.status(), Status::Conflict);
+        // Distinct from a single move with the wrong sign
+        let wrong_sign = put_board(&client, &id, "----O----");
+        assert_eq!(wrong_sign.status(), Status::UnprocessableEntity);
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], "---------");
+    }
+
+    #[test]
+    fn winning_move_ranks_highest() {
+        let client = client();
+        let id = create(&client, r#"{"board": "XX-OO----", "analysis": true}"#);
+
+        let ranked = body(client.get(format!("/games/{}/ranked-moves", id)).dispatch());
+
+        assert_eq!(ranked["to_move"], "X");
+        let moves = ranked["moves"].as_array().unwrap();
+        assert_eq!(moves.len(), 5);
+        assert_eq!(moves[0]["index"], 2);
+        let best = moves[0]["score"].as_i64().unwrap();
+        assert!(best > 0);
+        assert!(moves[1..]
+            .iter()
+            .all(|other| other["score"].as_i64().unwrap() < best));
+    }
+
+    #[rocket::async_test]
+    async fn concurrently_created_games_are_all_recorded_with_their_signs() {
+        capture_logs();
+        let client = rocket::local::asynchronous::Client::tracked(build(Config::from_env()))
+            .await
+            .unwrap();
+        let signs: Vec<&str> = (0..40)
+            .map(|i| if i % 2 == 0 { "X" } else { "O" })
+            .collect();
+
+        let responses = rocket::futures::future::join_all(signs.iter().map(|sign| {
+            client
+                .post("/games")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"board": "---------", "sign": "{}"}}"#, sign))
+                .dispatch()
+        }))
+        .await;
+
+        let players = client
+            .rocket()
+            .state::<PlayerList>()
+            .unwrap()
+            .player_map
+            .lock()
+            .unwrap();
+        assert_eq!(players.len(), signs.len());
+        for (response, sign) in responses.iter().zip(&signs) {
+            assert_eq!(response.status(), Status::Created);
+            let location = response.headers().get_one("Location").unwrap();
+            let id = location.rsplit('/').next().unwrap();
+            assert_eq!(players[id].to_string(), *sign);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn concurrently_created_matches_dont_exceed_the_game_limit() {
+        capture_logs();
+        let mut config = Config::from_env();
+        config.max_games = 3;
+        let client = rocket::local::asynchronous::Client::tracked(build(config))
+            .await
+            .unwrap();
+
+        let responses = rocket::futures::future::join_all((0..20).map(|_| {
+            client
+                .post("/matches")
+                .header(ContentType::JSON)
+                .body(r#"{"best_of": 3}"#)
+                .dispatch()
+        }))
+        .await;
+
+        let created = responses
+            .iter()
+            .filter(|response| response.status() == Status::Created)
+            .count();
+        assert_eq!(created, 3);
+        let games = client.rocket().state::<GameList>().unwrap();
+        assert_eq!(games.list.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn computer_move_kind_tells_how_the_move_was_chosen() {
+        let client = client();
+        let blocking = create(
+            &client,
+            r#"{"board": "X---O----", "resume": true, "sign": "X", "strategy": "block"}"#,
+        );
+        let game = body(put_board(&client, &blocking, "XX--O----"));
+        assert_eq!(game["computer_move_kind"], "block");
+        assert_eq!(game["board"], "XXO-O----");
+
+        let winning = create(
+            &client,
+            r#"{"board": "XO-XO----", "resume": true, "sign": "X", "strategy": "block"}"#,
+        );
+        let game = body(put_board(&client, &winning, "XOXXO----"));
+        assert_eq!(game["computer_move_kind"], "win");
+        assert_eq!(game["board"], "XOXXO--O-");
+
+        // The kind is only reported in the response to the move
+        let stored = body(client.get(format!("/games/{}", blocking)).dispatch());
+        assert_eq!(stored.get("computer_move_kind"), None);
+    }
+
+    #[test]
+    fn search_finds_every_game_in_the_position() {
+        let client = client();
+        let first = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+        let second = create(&client, r#"{"board": "X--------", "analysis": true}"#);
+        put_board(&client, &second, "X---O----");
+        create(&client, r#"{"board": "----X----", "analysis": true}"#);
+
+        let found = body(client.post("/games/search").body("x---o----\n").dispatch());
+
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(found, json::json!(expected));
+    }
+
+    #[test]
+    fn export_imported_into_a_fresh_store_is_equal() {
+        let with_key = |config: &mut Config| config.api_key = Some(String::from("key"));
+        let export = |client: &Client| {
+            let response = client
+                .get("/admin/export")
+                .header(Header::new("X-API-Key", "key"))
+                .dispatch();
+            let disposition = response.headers().get_one("Content-Disposition").unwrap();
+            assert!(disposition.starts_with("attachment; filename=\"games-"));
+            body(response)
+        };
+        let source = client_with(with_key);
+        let running = create(&source, r#"{"board": "---------", "sign": "X"}"#);
+        put_board(&source, &running, "----X----");
+        let finished = create(&source, r#"{"board": "XX-OO----", "analysis": true}"#);
+        put_board(&source, &finished, "XXXOO----");
+        let exported = export(&source);
+        assert_eq!(exported.as_array().unwrap().len(), 2);
+
+        let target = client_with(with_key);
+        let response = target
+            .post("/admin/import")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", "key"))
+            .body(exported.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(export(&target), exported);
+        let game = body(target.get(format!("/games/{}", finished)).dispatch());
+        assert_eq!(game["status"], "X_WON");
+        assert_eq!(game["game_over"], true);
+    }
+
+    #[test]
+    fn malformed_json_and_illegal_moves_have_distinct_statuses() {
+        let client = client();
+        let id = create(
+            &client,
+            r#"{"board": "X---O----", "resume": true, "sign": "X"}"#,
+        );
+        let put = |payload: &str| {
+            client
+                .put(format!("/games/{}", id))
+                .header(ContentType::JSON)
+                .body(payload)
+                .dispatch()
+                .status()
+        };
+
+        assert_eq!(put(r#"{"board": "XX--O----""#), Status::BadRequest);
+        assert_eq!(put(r#"{"cells": 9}"#), Status::BadRequest);
+        // Valid game JSON with an illegal move, overwriting the computer's sign
+        assert_eq!(
+            put(r#"{"board": "X---X----"}"#),
+            Status::UnprocessableEntity
+        );
+    }
+
+    #[test]
+    fn best_of_three_match_is_played_to_completion() {
+        let client = client();
+        let response = client
+            .post("/matches")
+            .header(ContentType::JSON)
+            .body(r#"{"best_of": 3, "strategy": "minimax", "sign": "X"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let id = id_of(&response);
+        let standings = || body(client.get(format!("/matches/{}", id)).dispatch());
+
+        // The player always takes the first empty cell, which loses every game to minimax
+        let mut current = standings();
+        while current["status"] == "RUNNING" {
+            let game = &current["current_game"];
+            let board = game["board"].as_str().unwrap();
+            let empty = board.find('-').unwrap();
+            let next = format!("{}X{}", &board[..empty], &board[empty + 1..]);
+            put_board(&client, game["id"].as_str().unwrap(), &next);
+            current = standings();
+        }
+
+        assert_eq!(current["status"], "COMPUTER_WON");
+        assert_eq!(current["computer_wins"], 2);
+        assert_eq!(current["player_wins"], 0);
+        assert_eq!(current["games"].as_array().unwrap().len(), 2);
+        assert_eq!(current["current_game"], Value::Null);
+    }
+
+    #[test]
+    fn bare_post_creates_a_game_on_the_default_board() {
+        let client = client();
+
+        let response = client.post("/games").dispatch();
+
+        assert_eq!(response.status(), Status::Created);
+        let game = body(
+            client
+                .get(format!("/games/{}", id_of(&response)))
+                .dispatch(),
+        );
+        assert_eq!(game["status"], "RUNNING");
+        // The computer opens the default empty board unless the player got X
+        let board = game["board"].as_str().unwrap();
+        assert!(board.matches('-').count() >= 8);
+    }
+
+    #[test]
+    fn admin_config_reports_the_settings_without_secrets() {
+        let client = client_with(|config| {
+            config.api_key = Some(String::from("key"));
+            config.max_games = 42;
+            config.max_games_per_owner = 3;
+        });
+
+        let response = client
+            .get("/admin/config")
+            .header(Header::new("X-API-Key", "key"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let config = body(response);
+        assert_eq!(config["base_url"], "http://127.0.0.1:8000/");
+        assert_eq!(config["default_strategy"], "random");
+        assert_eq!(config["board_size"], 9);
+        assert_eq!(config["max_games"], 42);
+        assert_eq!(config["max_games_per_owner"], 3);
+        assert_eq!(config["api_key"], "[redacted]");
+        assert!(!config.to_string().contains("\"key\""));
+    }
+
+    #[test]
+    fn session_cookie_scopes_my_games() {
+        let client = client();
+        let mine = |owner: Option<&str>| {
+            let mut request = client.get("/games/mine");
+            if let Some(owner) = owner {
+                request = request.header(Header::new("X-Owner-Token", owner.to_string()));
+            }
+            let mut ids: Vec<String> = body(request.dispatch())
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|game| game["id"].as_str().unwrap().to_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(mine(None), Vec::<String>::new());
+
+        // The first creation starts the session, the client sends its cookie from then on
+        let first = create(&client, r#"{"board": "---------"}"#);
+        assert!(client.cookies().get_private(auth::SESSION_COOKIE).is_some());
+        let second = create(&client, r#"{"board": "---------"}"#);
+
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(mine(None), expected);
+        // Another owner doesn't see the session's games
+        assert_eq!(mine(Some("someone-else")), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reachable_positions_from_the_empty_board() {
+        let client = client();
+        let id = create(&client, r#"{"board": "---------", "analysis": true}"#);
+        let positions = |query: &str| {
+            body(
+                client
+                    .get(format!("/games/{}/reachable?{}", id, query))
+                    .dispatch(),
+            )["positions"]
+                .clone()
+        };
+
+        assert_eq!(positions("depth=1"), 9);
+        assert_eq!(positions("depth=1&symmetric=true"), 3);
+        assert_eq!(positions("depth=2"), 9 + 72);
+        assert_eq!(positions("depth=2&symmetric=true"), 3 + 12);
+        let too_deep = client
+            .get(format!("/games/{}/reachable?depth=10", id))
+            .dispatch();
+        assert_eq!(too_deep.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn unknown_uuid_is_created_on_first_get_only_when_enabled() {
+        let id = Uuid::new_v4().to_string();
+
+        let client = client();
+        let response = client.get(format!("/games/{}", id)).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let client = client_with(|config| config.auto_create_games = true);
+        let response = client.get(format!("/games/{}", id)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let game = body(response);
+        assert_eq!(game["id"], id.as_str());
+        assert_eq!(game["status"], "RUNNING");
+        // Later requests find the same game
+        let again = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(again["board"], game["board"]);
+        let not_uuid = client.get("/games/not-a-uuid").dispatch();
+        assert_eq!(not_uuid.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn failed_auto_create_is_logged() {
+        let client = client_with(|config| {
+            config.auto_create_games = true;
+            config.default_board = String::from("XXXX-----");
+        });
+
+        let id = Uuid::new_v4().to_string();
+        let response = client.get(format!("/games/{}", id)).dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+        assert!(!logged("Unable to auto-create game from the default board").is_empty());
+    }
+
+    #[test]
+    fn each_move_token_only_moves_its_own_side() {
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------", "pvp": true}"#)
+            .dispatch();
+        let id = id_of(&response);
+        let token = |name: &str| response.headers().get_one(name).unwrap().to_string();
+        let (x, o) = (token("X-Move-Token-X"), token("X-Move-Token-O"));
+        assert_ne!(x, o);
+        let put = |board: &str, token: Option<&str>| {
+            let mut request = client
+                .put(format!("/games/{}", id))
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"board": "{}"}}"#, board));
+            if let Some(token) = token {
+                request = request.header(Header::new("X-Move-Token", token.to_string()));
+            }
+            request.dispatch().status()
+        };
+
+        assert_eq!(put("----X----", None), Status::Forbidden);
+        assert_eq!(put("----X----", Some(&o)), Status::Forbidden);
+        assert_eq!(put("----X----", Some(&x)), Status::Ok);
+        assert_eq!(put("O---X----", Some(&x)), Status::Forbidden);
+        assert_eq!(put("O---X----", Some(&o)), Status::Ok);
+    }
+
+    #[test]
+    fn cloned_pvp_game_gets_its_own_move_tokens() {
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "---------", "pvp": true}"#)
+            .dispatch();
+        let source_x = response
+            .headers()
+            .get_one("X-Move-Token-X")
+            .unwrap()
+            .to_string();
+        let response = client
+            .post(format!("/games/{}/clone", id_of(&response)))
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let copy = id_of(&response);
+        let x = response.headers().get_one("X-Move-Token-X").unwrap();
+        assert!(response.headers().get_one("X-Move-Token-O").is_some());
+        assert_ne!(x, source_x);
+        let put = |token: Option<&str>| {
+            let mut request = client
+                .put(format!("/games/{}", copy))
+                .header(ContentType::JSON)
+                .body(r#"{"board": "----X----"}"#);
+            if let Some(token) = token {
+                request = request.header(Header::new("X-Move-Token", token.to_string()));
+            }
+            request.dispatch().status()
+        };
+
+        assert_eq!(put(None), Status::Forbidden);
+        assert_eq!(put(Some(&source_x)), Status::Forbidden);
+        assert_eq!(put(Some(x)), Status::Ok);
+    }
+
+    #[test]
+    fn compact_form_matches_the_game_state() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+        let compact = |id: &str| client.get(format!("/games/{}/compact", id)).dispatch();
+
+        let state = compact(&id).into_string().unwrap();
+        assert_eq!(state, "X---O----|RUNNING|X");
+        let parsed = CompactState::try_from(state.as_str()).unwrap();
+        let game = body(client.get(format!("/games/{}", id)).dispatch());
+        assert_eq!(game["board"], parsed.board);
+        assert_eq!(game["status"], parsed.status.as_str());
+        assert_eq!(compact("unknown").status(), Status::NotFound);
+    }
+
+    #[test]
+    fn stats_and_compact_form_read_the_sign_kept_on_the_game() {
+        let client = client();
+        let response = client
+            .post("/games")
+            .header(ContentType::JSON)
+            .body(r#"{"board": "XX-OO----", "resume": true}"#)
+            .dispatch();
+        let id = id_of(&response);
+        let token = response
+            .headers()
+            .get_one("X-Owner-Token")
+            .unwrap()
+            .to_string();
+        // The game alone knows the player is X
+        client
+            .rocket()
+            .state::<PlayerList>()
+            .unwrap()
+            .player_map
+            .lock()
+            .unwrap()
+            .clear();
+
+        let compact = client.get(format!("/games/{}/compact", id)).dispatch();
+        assert_eq!(compact.into_string().unwrap(), "XX-OO----|RUNNING|X");
+        assert_eq!(put_board(&client, &id, "XXXOO----").status(), Status::Ok);
+        let stats = body(
+            client
+                .get(format!("/games/stats?token={}", token))
+                .dispatch(),
+        );
+        assert_eq!(
+            stats,
+            json::json!({"wins": 1, "losses": 0, "draws": 0, "running": 0})
+        );
+    }
+
+    #[test]
+    fn creation_returns_the_url_or_the_game() {
+        let client = client();
+        let post = |uri: &str| {
+            client
+                .post(uri.to_string())
+                .header(ContentType::JSON)
+                .body(r#"{"board": "---------", "sign": "X"}"#)
+                .dispatch()
+        };
+
+        let url = post("/games");
+        assert_eq!(url.status(), Status::Created);
+        let location = url.headers().get_one("Location").unwrap().to_string();
+        assert_eq!(body(url), location.as_str());
+
+        let game = post("/games?return=game");
+        assert_eq!(game.status(), Status::Created);
+        let location = game.headers().get_one("Location").unwrap().to_string();
+        let game = body(game);
+        assert_eq!(game["board"], "---------");
+        assert!(location.ends_with(&format!("/games/{}", game["id"].as_str().unwrap())));
+
+        assert_eq!(post("/games?return=both").status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn validate_move_reports_legal_overwriting_and_multi_cell_moves() {
+        let client = client();
+        let check = |payload: &str| {
+            body(
+                client
+                    .post("/validate-move")
+                    .header(ContentType::JSON)
+                    .body(payload)
+                    .dispatch(),
+            )
+        };
+
+        assert_eq!(
+            check(r#"{"board": "X---O----", "new_board": "XX--O----"}"#),
+            json::json!({"legal": true, "sign": "X", "index": 1, "reason": null})
+        );
+        assert_eq!(
+            check(r#"{"board": "X---O----", "index": 8}"#),
+            json::json!({"legal": true, "sign": "X", "index": 8, "reason": null})
+        );
+        assert_eq!(
+            check(r#"{"board": "X---O----", "index": 4}"#),
+            json::json!({"legal": false, "sign": "X", "index": null, "reason": "move overwrites a placed sign"})
+        );
+        assert_eq!(
+            check(r#"{"board": "X---O----", "new_board": "XX--O---X"}"#),
+            json::json!({"legal": false, "sign": "X", "index": null, "reason": "board changes more than one cell"})
+        );
+    }
+
+    #[test]
+    fn distance_to_a_forced_win_in_three_plies() {
+        let client = client();
+        // X forks at 8 and wins whichever threat O blocks
+        let id = create(&client, r#"{"board": "XO-O-X---", "analysis": true}"#);
+
+        let dtm = body(client.get(format!("/games/{}/dtm", id)).dispatch());
+
+        assert_eq!(dtm, json::json!({"to_move": "X", "plies": 3}));
+        put_board(&client, &id, "XO-O-X--X");
+        put_board(&client, &id, "XO-OOX--X");
+        put_board(&client, &id, "XOXOOX--X");
+        let finished = client.get(format!("/games/{}/dtm", id)).dispatch();
+        assert_eq!(finished.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn games_are_listed_sorted_by_each_field() {
+        let with_key = |config: &mut Config| config.api_key = Some(String::from("key"));
+        let source = client_with(with_key);
+        let empty = create(&source, r#"{"board": "---------", "analysis": true}"#);
+        let started = create(&source, r#"{"board": "---------", "analysis": true}"#);
+        for board in ["----X----", "O---X----"] {
+            put_board(&source, &started, board);
+        }
+        let won = create(&source, r#"{"board": "---------", "analysis": true}"#);
+        for board in [
+            "----X----",
+            "O---X----",
+            "O-X-X----",
+            "OOX-X----",
+            "OOX-X-X--",
+        ] {
+            put_board(&source, &won, board);
+        }
+
+        // Timestamps are set through an export, games created in a test share the same second
+        let mut export = body(
+            source
+                .get("/admin/export")
+                .header(Header::new("X-API-Key", "key"))
+                .dispatch(),
+        );
+        for game in export.as_array_mut().unwrap() {
+            let (created_at, updated_at) = match game["id"].as_str().unwrap() {
+                id if id == empty => (300, 100),
+                id if id == started => (100, 300),
+                _ => (200, 200),
+            };
+            game["created_at"] = json::json!(created_at);
+            game["updated_at"] = json::json!(updated_at);
+        }
+        // Exports outgrow the default JSON limit
+        let client = client_with(|config| {
+            with_key(config);
+            config.json_limit = 1 << 20;
+        });
+        client
+            .post("/admin/import")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", "key"))
+            .body(export.to_string())
+            .dispatch();
+        let listed = |query: &str| -> Vec<String> {
+            body(client.get(format!("/games?{}", query)).dispatch())
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|game| game["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let (empty, started, won) = (empty.as_str(), started.as_str(), won.as_str());
+
+        let mut by_id = vec![empty, started, won];
+        by_id.sort();
+        assert_eq!(listed(""), by_id);
+        assert_eq!(listed("sort=id"), by_id);
+        assert_eq!(listed("sort=created_at"), [started, won, empty]);
+        assert_eq!(listed("sort=created_at&order=desc"), [empty, won, started]);
+        assert_eq!(listed("sort=updated_at"), [empty, won, started]);
+        assert_eq!(listed("sort=move_count"), [empty, started, won]);
+        // RUNNING sorts before X_WON, the running games by id
+        let mut by_status: Vec<&str> = by_id.iter().copied().filter(|id| *id != won).collect();
+        by_status.push(won);
+        assert_eq!(listed("sort=status"), by_status);
+
+        let unknown = client.get("/games?sort=name").dispatch();
+        assert_eq!(unknown.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn computer_move_places_one_mark_for_the_side_to_move() {
+        let client = client();
+        let id = create(&client, r#"{"board": "X---O----", "analysis": true}"#);
+        let step = |id: &str| {
+            client
+                .post(format!("/games/{}/computer-move", id))
+                .dispatch()
+        };
+
+        let response = step(&id);
+
+        assert_eq!(response.status(), Status::Ok);
+        let board = body(response)["board"].as_str().unwrap().to_string();
+        assert_eq!(board.matches('X').count(), 2);
+        assert_eq!(board.matches('O').count(), 1);
+        assert!(board.starts_with('X') && board.as_bytes()[4] == b'O');
+
+        // Refused on the player's turn of a regular game
+        let regular = create(&client, r#"{"board": "---------", "sign": "X"}"#);
+        assert_eq!(step(&regular).status(), Status::Conflict);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::config::Config;
+use crate::game::{CreateError, Game, GameList, GameStatus, NewGame, PlayerList};
+use crate::sign::Sign;
+use crate::strategy::Strategies;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Maximum amount of games in a single match
+pub const MAX_BEST_OF: u32 = 99;
+
+/// Payload of a request to create a match
+#[derive(Deserialize)]
+pub struct NewMatch {
+    /// Amount of games the match is played over, odd and at most MAX_BEST_OF
+    pub best_of: u32,
+
+    /// Name of the strategy the computer chooses its moves with in every game, random when omitted
+    #[serde(default)]
+    pub strategy: Option<String>,
+
+    /// Sign the player plays in every game, assigned randomly for each game when omitted
+    #[serde(default)]
+    pub sign: Option<String>,
+}
+
+/// Status of a match
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MatchStatus {
+    Running,
+    PlayerWon,
+    ComputerWon,
+    Draw,
+}
+
+/// A series of games between the player and the computer
+///
+/// The match is won by the first side to win a majority of the best_of games. If draws leave neither
+/// side with a majority once best_of games have been played, the side with more wins takes the match
+/// or it ends in a draw.
+/// The games are regular games, played through the game endpoints.
+#[derive(Serialize, Clone)]
+pub struct Match {
+    /// ID of the match
+    id: String,
+
+    /// Amount of games the match is played over
+    best_of: u32,
+
+    /// Strategy of the computer in every game, random when None
+    strategy: Option<String>,
+
+    /// Sign of the player in every game, random for each game when None
+    sign: Option<String>,
+
+    /// Games won by the player
+    player_wins: u32,
+
+    /// Games won by the computer
+    computer_wins: u32,
+
+    /// Drawn games
+    draws: u32,
+
+    /// Status of the match
+    status: MatchStatus,
+
+    /// IDs of the games of the match so far, the last one being the current game while the match runs
+    games: Vec<String>,
+}
+
+impl Match {
+    /// Creates a match and starts its first game, storing the game in the game list
+    ///
+    /// The game list is taken locked, so callers can check whether there's room for the game under the
+    /// same lock it's stored with.
+    ///
+    /// Returns the reason the first game couldn't be created as an error, e.g. an unknown strategy
+    ///
+    /// # Arguments
+    ///
+    /// * 'new_match' - Creation request containing the options of the match
+    ///
+    /// * 'games' - The locked map of all games
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn new(
+        new_match: &NewMatch,
+        games: &mut HashMap<String, Game>,
+        player_list: &PlayerList,
+        config: &Config,
+        strategies: &Strategies,
+    ) -> Result<Match, CreateError> {
+        let mut series = Match {
+            id: Uuid::new_v4().to_string(),
+            best_of: new_match.best_of,
+            strategy: new_match.strategy.clone(),
+            sign: new_match.sign.clone(),
+            player_wins: 0,
+            computer_wins: 0,
+            draws: 0,
+            status: MatchStatus::Running,
+            games: vec![],
+        };
+        series.start_game(games, player_list, config, strategies)?;
+        Ok(series)
+    }
+
+    /// Returns the ID of the match
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+
+    /// Returns the ID of the game being played, None once the match is over
+    pub fn current_game(&self) -> Option<&String> {
+        match self.status {
+            MatchStatus::Running => self.games.last(),
+            _ => None,
+        }
+    }
+
+    /// Records the result of the current game once it's over and starts the next game, or ends the match
+    /// if it has been decided
+    ///
+    /// Does nothing while the current game is running, or if it has been deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_list' - Maintains a map of all games in a mutex to handle asynchronous requests
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn advance(
+        &mut self,
+        game_list: &GameList,
+        player_list: &PlayerList,
+        config: &Config,
+        strategies: &Strategies,
+    ) -> Result<(), CreateError> {
+        let current = match self.current_game() {
+            Some(id) => id.clone(),
+            None => return Ok(()),
+        };
+        let mut games = game_list.list.lock().unwrap();
+        let status = match games.get(&current) {
+            Some(game) if game.is_over() => game
+                .get_status()
+                .as_deref()
+                .and_then(|status| GameStatus::try_from(status).ok()),
+            _ => None,
+        };
+        let player_sign = player_list
+            .player_map
+            .lock()
+            .unwrap()
+            .get(&current)
+            .copied();
+
+        match (status, player_sign) {
+            (Some(GameStatus::DRAW), _) => self.draws += 1,
+            (Some(GameStatus::XWon), Some(Sign::X)) | (Some(GameStatus::OWon), Some(Sign::O)) => {
+                self.player_wins += 1
+            }
+            (Some(GameStatus::XWon | GameStatus::OWon), Some(_)) => self.computer_wins += 1,
+            _ => return Ok(()), // Still running, or gone along with its player
+        }
+
+        let majority = self.best_of / 2 + 1;
+        let played = self.games.len() as u32;
+        self.status = if self.player_wins >= majority {
+            MatchStatus::PlayerWon
+        } else if self.computer_wins >= majority {
+            MatchStatus::ComputerWon
+        } else if played < self.best_of {
+            return self.start_game(&mut games, player_list, config, strategies);
+        } else if self.player_wins > self.computer_wins {
+            MatchStatus::PlayerWon
+        } else if self.computer_wins > self.player_wins {
+            MatchStatus::ComputerWon
+        } else {
+            MatchStatus::Draw
+        };
+        Ok(())
+    }
+
+    /// Creates the next game of the match from an empty board and stores it in the game list
+    ///
+    /// # Arguments
+    ///
+    /// * 'games' - The locked map of all games
+    ///
+    /// * 'player_list' - Maintains a map of all players and their sign choice (X or O) in a mutex to handle async requests
+    ///
+    /// * 'config' - Runtime configuration of the API
+    ///
+    /// * 'strategies' - Registry of the strategies the computer can choose its moves with
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    fn start_game(
+        &mut self,
+        games: &mut HashMap<String, Game>,
+        player_list: &PlayerList,
+        config: &Config,
+        strategies: &Strategies,
+    ) -> Result<(), CreateError> {
+        let new_game = NewGame::empty(self.strategy.clone(), self.sign.clone());
+        let game = Game::new(&new_game, player_list, config, strategies)?;
+        let id = game.get_id().clone().unwrap(); // New games always have an ID
+        games.insert(id.clone(), game);
+        self.games.push(id);
+        Ok(())
+    }
+}
+
+/// Container for a HashMap of matches by ID, analogous to GameList
+pub struct MatchList {
+    pub list: Arc<Mutex<HashMap<String, Match>>>,
+}
@@ -0,0 +1,1077 @@
+use crate::ai::MAX_TREE_DEPTH;
+use crate::tournament::MAX_TOURNAMENT_GAMES;
+use rocket::serde::json::{json, Value};
+
+/// Builds the OpenAPI 3 description of the API
+///
+/// The document is built in code next to the handlers so that it is served by the API itself
+/// and updated together with the routes.
+pub fn document() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Tic Tac Toe API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/": {
+                "get": {
+                    "summary": "Describe the API",
+                    "responses": {
+                        "200": response("Name, version and endpoints of the API", json!({
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "version": { "type": "string" },
+                                "endpoints": { "type": "array", "items": { "type": "string" } },
+                            },
+                        })),
+                    },
+                },
+            },
+            "/games": {
+                "get": {
+                    "summary": "List all games",
+                    "parameters": [
+                        { "name": "include_finished", "in": "query", "required": false, "schema": { "type": "boolean", "default": false } },
+                        { "name": "sort", "in": "query", "required": false, "schema": {
+                            "type": "string",
+                            "enum": ["id", "created_at", "updated_at", "move_count", "status"],
+                            "default": "id",
+                        } },
+                        { "name": "order", "in": "query", "required": false, "schema": { "type": "string", "enum": ["asc", "desc"], "default": "asc" } },
+                    ],
+                    "responses": {
+                        "200": response("Array of games", schema_array("Game")),
+                        "400": error_response("Unknown sort field or order"),
+                    },
+                },
+                "options": allow_operation(),
+                "post": {
+                    "summary": "Create a new game from a starting board, or the default board without a body",
+                    "parameters": [
+                        { "name": "return", "in": "query", "required": false, "schema": { "type": "string", "enum": ["url", "game"], "default": "url" } },
+                    ],
+                    "requestBody": {
+                        "required": false,
+                        "content": { "application/json": { "schema": schema_ref("NewGame") } },
+                    },
+                    "responses": {
+                        "201": {
+                            "description": "URL of the created game, or the game itself with return=game",
+                            "headers": { "Location": { "schema": { "type": "string", "format": "uri" } } },
+                            "content": { "application/json": { "schema": {
+                                "oneOf": [{ "type": "string", "format": "uri" }, schema_ref("Game")],
+                            } } },
+                        },
+                        "400": error_response("Malformed board, unknown strategy or unknown return value"),
+                        "409": error_response("Illegal turn balance or unreachable position"),
+                        "413": error_response("Request body too large"),
+                        "422": error_response("Body is not a game creation request"),
+                        "429": error_response("Too many requests, or too many running games for the owner token"),
+                        "503": error_response("Maximum number of games reached, or the request timed out"),
+                    },
+                },
+            },
+            "/games/grouped": {
+                "get": {
+                    "summary": "List the IDs of all games grouped by status",
+                    "responses": {
+                        "200": response("Arrays of game IDs keyed by status", json!({
+                            "type": "object",
+                            "properties": {
+                                "RUNNING": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                                "X_WON": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                                "O_WON": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                                "DRAW": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                            },
+                        })),
+                    },
+                },
+            },
+            "/games/ids": {
+                "get": operation("List the IDs of all games", "Array of game IDs", json!({
+                    "type": "array",
+                    "items": { "type": "string", "format": "uuid" },
+                })),
+            },
+            "/games/stats": {
+                "parameters": [
+                    { "name": "token", "in": "query", "required": true, "schema": { "type": "string" } },
+                ],
+                "get": operation("Get the win/loss/draw record of an owner token", "Record of the owner", json!({
+                    "type": "object",
+                    "properties": {
+                        "wins": { "type": "integer" },
+                        "losses": { "type": "integer" },
+                        "draws": { "type": "integer" },
+                        "running": { "type": "integer" },
+                    },
+                })),
+            },
+            "/games/mine": {
+                "get": {
+                    "summary": "List the games of the owner token from the X-Owner-Token header or the session cookie",
+                    "responses": {
+                        "200": response("Array of games, empty without an owner token", schema_array("Game")),
+                    },
+                },
+            },
+            "/games/{id}": {
+                "parameters": [id_parameter()],
+                "options": allow_operation(),
+                "get": {
+                    "summary": "Get a game",
+                    "parameters": [
+                        { "name": "include_archived", "in": "query", "required": false, "schema": { "type": "boolean", "default": false } },
+                    ],
+                    "responses": {
+                        "200": response("The game, created first if auto-creating games is configured", schema_ref("Game")),
+                        "404": { "description": "Game not found" },
+                        "410": { "description": "Game was recently deleted" },
+                        "503": { "description": "Maximum number of games reached while auto-creating the game" },
+                    },
+                },
+                "put": {
+                    "summary": "Make a move, the computer replies with its own move",
+                    "parameters": [
+                        { "name": "If-Match", "in": "header", "required": false, "schema": { "type": "string" } },
+                        { "name": "X-Move-Token", "in": "header", "required": false, "description": "Move token of the side to move, required in PvP games", "schema": { "type": "string" } },
+                    ],
+                    "requestBody": json_body("Game"),
+                    "responses": {
+                        "200": {
+                            "description": "The updated game",
+                            "headers": {
+                                "ETag": { "schema": { "type": "string" } },
+                                "X-Computer-Move": { "description": "Cell the computer replied in, missing if it didn't reply", "schema": { "type": "integer" } },
+                            },
+                            "content": { "application/json": { "schema": schema_ref("Game") } },
+                        },
+                        "400": { "description": "Body is not a game" },
+                        "422": { "description": "Illegal move" },
+                        "404": { "description": "Game not found" },
+                        "403": { "description": "PvP game and the move token is not the token of the side to move" },
+                        "409": { "description": "The board changes more than one cell" },
+                        "412": { "description": "The game has changed since the ETag in If-Match" },
+                        "429": { "description": "Too many requests" },
+                        "503": { "description": "The request timed out, the game is unchanged" },
+                    },
+                },
+                "patch": {
+                    "summary": "Make a move with a JSON Patch of the game, the computer replies with its own move",
+                    "parameters": [
+                        { "name": "If-Match", "in": "header", "required": false, "schema": { "type": "string" } },
+                        { "name": "X-Move-Token", "in": "header", "required": false, "description": "Move token of the side to move, required in PvP games", "schema": { "type": "string" } },
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json-patch+json": {
+                                "schema": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "object",
+                                        "required": ["op", "path"],
+                                        "properties": {
+                                            "op": { "type": "string", "enum": ["add", "remove", "replace", "move", "copy", "test"] },
+                                            "path": { "type": "string" },
+                                            "from": { "type": "string" },
+                                            "value": {},
+                                        },
+                                    },
+                                },
+                            },
+                        },
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The updated game",
+                            "headers": {
+                                "ETag": { "schema": { "type": "string" } },
+                                "X-Computer-Move": { "description": "Cell the computer replied in, missing if it didn't reply", "schema": { "type": "integer" } },
+                            },
+                            "content": { "application/json": { "schema": schema_ref("Game") } },
+                        },
+                        "403": error_response("PvP game and the move token is not the token of the side to move"),
+                        "404": error_response("Game not found"),
+                        "409": error_response("The patched board changes more than one cell"),
+                        "412": error_response("The game has changed since the ETag in If-Match"),
+                        "422": error_response("Patch failed to apply or produced an illegal move"),
+                        "429": { "description": "Too many requests" },
+                        "503": error_response("The request timed out, the game is unchanged"),
+                    },
+                },
+                "delete": {
+                    "summary": "Delete a game, or archive it with soft=true",
+                    "parameters": [
+                        { "name": "soft", "in": "query", "required": false, "schema": { "type": "boolean", "default": false } },
+                    ],
+                    "responses": {
+                        "200": response("The deleted or archived game", schema_ref("Game")),
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/eval": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Evaluate the position with minimax from the perspective of the side to move",
+                    "responses": {
+                        "200": response("Score and best move", json!({
+                            "type": "object",
+                            "properties": {
+                                "to_move": { "type": "string", "enum": ["X", "O"] },
+                                "score": { "type": "integer", "minimum": -10, "maximum": 10 },
+                                "best_move": { "type": "integer", "minimum": 0, "maximum": 8 },
+                            },
+                        })),
+                        "400": { "description": "Game is over" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/ranked-moves": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Rank the legal moves with minimax from the perspective of the side to move",
+                    "responses": {
+                        "200": response("Legal moves, best first", json!({
+                            "type": "object",
+                            "properties": {
+                                "to_move": { "type": "string", "enum": ["X", "O"] },
+                                "moves": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "object",
+                                        "properties": {
+                                            "index": { "type": "integer", "minimum": 0, "maximum": 8 },
+                                            "score": { "type": "integer", "minimum": -10, "maximum": 10 },
+                                        },
+                                    },
+                                },
+                            },
+                        })),
+                        "400": { "description": "Game is over" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/outcome": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Result of the game with perfect play by both sides",
+                    "responses": {
+                        "200": response("The side to move and the outcome", json!({
+                            "type": "object",
+                            "properties": {
+                                "to_move": { "type": "string", "enum": ["X", "O"] },
+                                "outcome": { "type": "string", "enum": ["X_WON", "O_WON", "DRAW"] },
+                            },
+                        })),
+                        "400": { "description": "Game is over" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/dtm": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Plies until the game ends with perfect play by both sides",
+                    "responses": {
+                        "200": response("The side to move and the plies left", json!({
+                            "type": "object",
+                            "properties": {
+                                "to_move": { "type": "string", "enum": ["X", "O"] },
+                                "plies": { "type": "integer" },
+                            },
+                        })),
+                        "400": { "description": "Game is over" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/tree": {
+                "parameters": [
+                    id_parameter(),
+                    {
+                        "name": "depth",
+                        "in": "query",
+                        "schema": { "type": "integer", "minimum": 0, "maximum": MAX_TREE_DEPTH, "default": 1 },
+                    },
+                ],
+                "get": {
+                    "summary": "Minimax game tree from the current position",
+                    "responses": {
+                        "200": response("The root of the tree", schema_ref("TreeNode")),
+                        "400": error_response("Game is over or depth is too large"),
+                        "404": error_response("Game not found"),
+                    },
+                },
+            },
+            "/games/{id}/reachable": {
+                "parameters": [
+                    id_parameter(),
+                    {
+                        "name": "depth",
+                        "in": "query",
+                        "schema": { "type": "integer", "minimum": 0, "maximum": 9, "default": 1 },
+                    },
+                    { "name": "symmetric", "in": "query", "required": false, "schema": { "type": "boolean", "default": false } },
+                ],
+                "get": {
+                    "summary": "Count the distinct positions reachable within depth plies",
+                    "responses": {
+                        "200": response("Amount of reachable positions", json!({
+                            "type": "object",
+                            "properties": {
+                                "depth": { "type": "integer" },
+                                "symmetric": { "type": "boolean" },
+                                "positions": { "type": "integer" },
+                            },
+                        })),
+                        "400": error_response("Depth is too large"),
+                        "404": error_response("Game not found"),
+                    },
+                },
+            },
+            "/games/{id}/win-type": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Classify the line the game was won with",
+                    "responses": {
+                        "200": response("The winning line", json!({
+                            "type": "object",
+                            "properties": {
+                                "sign": { "type": "string", "enum": ["X", "O"] },
+                                "kind": { "type": "string", "enum": ["ROW", "COLUMN", "DIAGONAL"] },
+                                "index": { "type": "integer", "minimum": 0, "maximum": 2 },
+                                "cells": { "type": "array", "items": { "type": "integer" } },
+                            },
+                        })),
+                        "204": { "description": "Game has no winning line" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/threats": {
+                "parameters": [id_parameter()],
+                "get": operation("Cells each side could win in with their next move", "Winning cells by side", json!({
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "array", "items": { "type": "integer" } },
+                        "o": { "type": "array", "items": { "type": "integer" } },
+                    },
+                })),
+            },
+            "/games/{id}/bitboard": {
+                "parameters": [id_parameter()],
+                "get": operation("Get the board as bitboards of X and O", "Bitboards, bit i standing for cell i", json!({
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "integer", "minimum": 0, "maximum": 511 },
+                        "o": { "type": "integer", "minimum": 0, "maximum": 511 },
+                    },
+                })),
+            },
+            "/games/{id}/forks": {
+                "parameters": [id_parameter()],
+                "get": operation("Cells each side could create two threats at once in", "Forking cells by side", json!({
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "array", "items": { "type": "integer" } },
+                        "o": { "type": "array", "items": { "type": "integer" } },
+                    },
+                })),
+            },
+            "/games/{id}/events": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Stream the game as server-sent events every time it changes",
+                    "responses": {
+                        "200": {
+                            "description": "Stream of games",
+                            "content": { "text/event-stream": { "schema": { "type": "string" } } },
+                        },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/wait": {
+                "parameters": [
+                    id_parameter(),
+                    { "name": "since", "in": "query", "required": false, "schema": { "type": "integer" } },
+                ],
+                "get": {
+                    "summary": "Wait for the game to change from the given version",
+                    "responses": {
+                        "200": response("The changed game", schema_ref("Game")),
+                        "304": { "description": "No change before the timeout" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/view": {
+                "parameters": [
+                    id_parameter(),
+                    { "name": "token", "in": "query", "required": true, "schema": { "type": "string" } },
+                ],
+                "get": operation("Get a game with its read-only token", "The game", schema_ref("Game")),
+            },
+            "/games/{id}/ascii": {
+                "parameters": [id_parameter()],
+                "get": text_operation("Get the board as a plain text grid", "text/plain"),
+            },
+            "/games/{id}/notation": {
+                "parameters": [id_parameter()],
+                "get": text_operation("Get the moves as a transcript of sign and cell index pairs", "text/plain"),
+            },
+            "/games/{id}/compact": {
+                "parameters": [id_parameter()],
+                "get": text_operation("Get the board, status and side to move as a single line separated by '|'", "text/plain"),
+            },
+            "/games/{id}/at/{ply}": {
+                "parameters": [
+                    id_parameter(),
+                    { "name": "ply", "in": "path", "required": true, "schema": { "type": "integer", "minimum": 0 } },
+                ],
+                "get": {
+                    "summary": "Get the board as it was after a number of moves",
+                    "responses": {
+                        "200": response("The board after the moves", json!({
+                            "type": "object",
+                            "properties": {
+                                "ply": { "type": "integer" },
+                                "board": { "type": "string" },
+                            },
+                        })),
+                        "400": { "description": "Fewer moves have been made" },
+                        "404": { "description": "Game not found" },
+                    },
+                },
+            },
+            "/games/{id}/svg": {
+                "parameters": [id_parameter()],
+                "get": text_operation("Get the board as an SVG image", "image/svg+xml"),
+            },
+            "/games/{id}/canonical": {
+                "parameters": [id_parameter()],
+                "get": operation("Get the canonical form of the board", "Canonical board", json!({
+                    "type": "object",
+                    "properties": {
+                        "canonical": { "type": "string" },
+                        "transform": { "type": "string" },
+                    },
+                })),
+            },
+            "/games/{id}/repetitions": {
+                "parameters": [id_parameter()],
+                "get": operation("Count the occurrences of the current position", "Canonical board and its occurrences", json!({
+                    "type": "object",
+                    "properties": {
+                        "canonical": { "type": "string" },
+                        "count": { "type": "integer", "minimum": 1 },
+                    },
+                })),
+            },
+            "/games/{id}/simulate": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Preview a move and the computer's reply without storing them",
+                    "requestBody": json_body("Game"),
+                    "responses": {
+                        "200": response("The game after the move and reply", schema_ref("Game")),
+                        "400": { "description": "Body is not a game" },
+                        "422": { "description": "Illegal move" },
+                        "404": { "description": "Game not found" },
+                        "409": { "description": "The board changes more than one cell" },
+                    },
+                },
+            },
+            "/games/{id}/moves": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Play a sequence of moves with the computer replying between each",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "array",
+                            "items": { "type": "integer", "minimum": 0, "maximum": 8 },
+                        } } },
+                    },
+                    "responses": {
+                        "200": response("The game after the played moves", json!({
+                            "type": "object",
+                            "properties": {
+                                "game": schema_ref("Game"),
+                                "applied": { "type": "integer" },
+                                "error": { "type": "string", "nullable": true },
+                            },
+                        })),
+                        "403": { "description": "PvP games are played one move at a time" },
+                        "404": { "description": "Game not found" },
+                        "429": { "description": "Too many requests" },
+                    },
+                },
+            },
+            "/games/{id}/autoplay": {
+                "parameters": [id_parameter()],
+                "post": operation("Play an autoplay game to completion", "The finished game", schema_ref("Game")),
+            },
+            "/games/{id}/undo-computer": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Take back the computer's last reply",
+                    "parameters": [
+                        { "name": "reply", "in": "query", "required": false, "schema": { "type": "boolean", "default": false } },
+                    ],
+                    "responses": {
+                        "200": response("The game without the computer's reply", schema_ref("Game")),
+                        "404": error_response("Game not found"),
+                        "409": error_response("Last move was not made by the computer"),
+                    },
+                },
+            },
+            "/games/{id}/computer-move": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Make one computer move for the side to move",
+                    "responses": {
+                        "200": {
+                            "description": "The game after the computer's move",
+                            "headers": {
+                                "ETag": { "schema": { "type": "string" } },
+                                "X-Computer-Move": { "description": "Cell the computer played in", "schema": { "type": "integer" } },
+                            },
+                            "content": { "application/json": { "schema": schema_ref("Game") } },
+                        },
+                        "403": error_response("PvP game"),
+                        "404": error_response("Game not found"),
+                        "409": error_response("Game is over or it's the player's turn"),
+                    },
+                },
+            },
+            "/games/{id}/swap": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Take over the sign of the computer's opening move (pie rule)",
+                    "responses": {
+                        "200": response("The game after the computer's reply with its new sign", schema_ref("Game")),
+                        "404": error_response("Game not found"),
+                        "409": error_response("Game is over or more than the computer's opening move has been played"),
+                    },
+                },
+            },
+            "/games/{id}/clone": {
+                "parameters": [id_parameter()],
+                "post": {
+                    "summary": "Copy a game into a new independent game",
+                    "responses": {
+                        "201": created_response("URL of the copy"),
+                        "404": error_response("Game not found"),
+                        "429": error_response("Too many running games for the owner token"),
+                        "503": error_response("Maximum number of games reached"),
+                    },
+                },
+            },
+            "/games/import": {
+                "post": {
+                    "summary": "Create a game by replaying moves in the notation of the notation endpoint",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "text/plain": { "schema": { "type": "string", "example": "X:4 O:0 X:8" } } },
+                    },
+                    "responses": {
+                        "201": {
+                            "description": "The imported game",
+                            "headers": { "Location": { "schema": { "type": "string", "format": "uri" } } },
+                            "content": { "application/json": { "schema": schema_ref("Game") } },
+                        },
+                        "400": error_response("Malformed move"),
+                        "409": error_response("Moves can't be played in order"),
+                        "429": error_response("Too many running games for the owner token"),
+                        "503": error_response("Maximum number of games reached"),
+                    },
+                },
+            },
+            "/games/search": {
+                "post": {
+                    "summary": "Find the games whose current board is the submitted board",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "text/plain": { "schema": { "type": "string", "example": "X---O----" } } },
+                    },
+                    "responses": {
+                        "200": response("IDs of the matching games", json!({
+                            "type": "array",
+                            "items": { "type": "string", "format": "uuid" },
+                        })),
+                        "400": error_response("Malformed board"),
+                        "409": error_response("Impossible sign counts"),
+                    },
+                },
+            },
+            "/tournament": {
+                "post": {
+                    "summary": "Play self-play games between two strategies without storing them",
+                    "requestBody": json_body("Tournament"),
+                    "responses": {
+                        "200": response("Aggregate results", schema_ref("TournamentResult")),
+                        "400": error_response("Unknown strategy"),
+                        "429": { "description": "Too many requests" },
+                    },
+                },
+            },
+            "/matches": {
+                "post": {
+                    "summary": "Create a best of N match against the computer and start its first game",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "required": ["best_of"],
+                            "properties": {
+                                "best_of": { "type": "integer", "minimum": 1, "maximum": 99 },
+                                "strategy": { "type": "string" },
+                                "sign": { "type": "string" },
+                            },
+                        } } },
+                    },
+                    "responses": {
+                        "201": created_response("URL of the created match"),
+                        "400": error_response("Even best_of, unknown strategy or invalid sign"),
+                        "429": { "description": "Too many requests" },
+                        "503": error_response("Maximum number of games reached"),
+                    },
+                },
+            },
+            "/matches/{id}": {
+                "parameters": [id_parameter()],
+                "get": {
+                    "summary": "Get the standings of a match, starting its next game once the current one has ended",
+                    "responses": {
+                        "200": response("Standings and current game", json!({
+                            "type": "object",
+                            "properties": {
+                                "id": { "type": "string", "format": "uuid" },
+                                "best_of": { "type": "integer" },
+                                "strategy": { "type": "string", "nullable": true },
+                                "sign": { "type": "string", "nullable": true },
+                                "player_wins": { "type": "integer" },
+                                "computer_wins": { "type": "integer" },
+                                "draws": { "type": "integer" },
+                                "status": { "type": "string", "enum": ["RUNNING", "PLAYER_WON", "COMPUTER_WON", "DRAW"] },
+                                "games": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                                "current_game": { "allOf": [schema_ref("Game")], "nullable": true },
+                            },
+                        })),
+                        "404": { "description": "Match not found" },
+                    },
+                },
+            },
+            "/games/{id}/board": {
+                "parameters": [id_parameter()],
+                "put": {
+                    "summary": "Set the board to a legal position without a computer reply, admin only",
+                    "requestBody": json_body("Game"),
+                    "responses": {
+                        "200": response("The game with the new board", schema_ref("Game")),
+                        "400": error_response("Invalid board"),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                        "404": error_response("Game not found"),
+                        "409": error_response("Position is not legal for the game"),
+                    },
+                },
+            },
+            "/games/{id}/status": {
+                "parameters": [id_parameter()],
+                "put": {
+                    "summary": "Set the status without touching the board, admin only",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "required": ["status"],
+                            "properties": {
+                                "status": { "type": "string", "enum": ["RUNNING", "X_WON", "O_WON", "DRAW"] },
+                            },
+                        } } },
+                    },
+                    "responses": {
+                        "200": response("The game with the new status", schema_ref("Game")),
+                        "400": error_response("Invalid status"),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                        "404": error_response("Game not found"),
+                    },
+                },
+            },
+            "/convert": {
+                "post": {
+                    "summary": "Convert a board between text formats",
+                    "requestBody": json_body("ConvertRequest"),
+                    "responses": {
+                        "200": response("The converted board", json!({
+                            "type": "object",
+                            "properties": { "board": { "type": "string" } },
+                        })),
+                        "400": error_response("Malformed board"),
+                        "409": error_response("Illegal turn balance"),
+                    },
+                },
+            },
+            "/validate-move": {
+                "post": {
+                    "summary": "Check whether a move is legal without a stored game",
+                    "requestBody": json_body("MoveCheck"),
+                    "responses": {
+                        "200": response("Whether the move is legal", json!({
+                            "type": "object",
+                            "properties": {
+                                "legal": { "type": "boolean" },
+                                "sign": { "type": "string", "enum": ["X", "O"] },
+                                "index": { "type": "integer", "nullable": true },
+                                "reason": { "type": "string", "nullable": true },
+                            },
+                        })),
+                        "400": error_response("Malformed board, or not exactly one of new_board and index"),
+                        "409": error_response("Impossible position"),
+                    },
+                },
+            },
+            "/admin/stats": {
+                "get": {
+                    "summary": "Size of the in-memory store, admin only",
+                    "responses": {
+                        "200": response("Store metrics", json!({
+                            "type": "object",
+                            "properties": {
+                                "games": { "type": "integer" },
+                                "archived_games": { "type": "integer" },
+                                "players": { "type": "integer" },
+                                "tokens": { "type": "integer" },
+                                "approximate_bytes": { "type": "integer" },
+                            },
+                        })),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                    },
+                },
+            },
+            "/admin/config": {
+                "get": {
+                    "summary": "Effective runtime configuration with the API key redacted, admin only",
+                    "responses": {
+                        "200": response("Configuration of the server, one property per setting", json!({
+                            "type": "object",
+                            "properties": {
+                                "base_url": { "type": "string" },
+                                "default_strategy": { "type": "string" },
+                                "board_size": { "type": "integer" },
+                                "api_key": { "type": "string", "nullable": true, "enum": ["[redacted]"] },
+                            },
+                            "additionalProperties": true,
+                        })),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                    },
+                },
+            },
+            "/admin/gc": {
+                "post": {
+                    "summary": "Remove player sign choices and tokens of games that no longer exist, admin only",
+                    "responses": {
+                        "200": response("Amount of sign choices and token entries removed", json!({
+                            "type": "object",
+                            "properties": {
+                                "removed": { "type": "integer" },
+                                "removed_tokens": { "type": "integer" },
+                            },
+                        })),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                    },
+                },
+            },
+            "/admin/export": {
+                "get": {
+                    "summary": "Download every game with the sign of its player, admin only",
+                    "responses": {
+                        "200": {
+                            "description": "The games, as an attachment",
+                            "headers": { "Content-Disposition": { "schema": { "type": "string" } } },
+                            "content": { "application/json": { "schema": {
+                                "type": "array",
+                                "items": {
+                                    "allOf": [
+                                        schema_ref("Game"),
+                                        { "type": "object", "properties": { "player_sign": { "type": "string", "enum": ["X", "O"] } } },
+                                    ],
+                                },
+                            } } },
+                        },
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                    },
+                },
+            },
+            "/admin/import": {
+                "post": {
+                    "summary": "Load the games of an export, merging them or replacing the store, admin only",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "type": "array", "items": { "type": "object" } } } },
+                    },
+                    "responses": {
+                        "200": response("Amount of games loaded", json!({
+                            "type": "object",
+                            "properties": {
+                                "imported": { "type": "integer" },
+                                "replaced": { "type": "boolean" },
+                            },
+                        })),
+                        "400": error_response("A game has no id"),
+                        "401": { "description": "Missing or wrong API key, or no API key configured" },
+                        "413": error_response("Request body too large"),
+                        "422": error_response("A game is inconsistent"),
+                    },
+                },
+            },
+            "/admin/reset": {
+                "post": operation("Remove all games, matches and tokens", "Amount of games removed", json!({
+                    "type": "object",
+                    "properties": { "removed": { "type": "integer" } },
+                })),
+            },
+        },
+        "components": {
+            "schemas": {
+                "Game": {
+                    "type": "object",
+                    "required": ["board"],
+                    "properties": {
+                        "id": { "type": "string", "format": "uuid", "readOnly": true },
+                        "board": { "type": "string", "minLength": 9, "maxLength": 9 },
+                        "status": { "type": "string", "enum": ["RUNNING", "X_WON", "O_WON", "DRAW"], "readOnly": true },
+                        "game_over": { "type": "boolean", "readOnly": true },
+                        "ended_by": { "type": "string", "enum": ["PLAYER", "COMPUTER", "TIMEOUT"], "nullable": true, "readOnly": true },
+                        "turn": { "type": "integer", "readOnly": true },
+                        "autoplay": { "type": "boolean" },
+                        "analysis": { "type": "boolean" },
+                        "seed": { "type": "integer", "format": "int64", "minimum": 0, "readOnly": true },
+                        "strategy": { "type": "string", "readOnly": true },
+                        "time_used": {
+                            "type": "object",
+                            "description": "Thinking time of each side in milliseconds",
+                            "properties": { "x": { "type": "integer" }, "o": { "type": "integer" } },
+                            "readOnly": true,
+                        },
+                        "version": { "type": "integer", "readOnly": true },
+                        "archived": { "type": "boolean", "readOnly": true },
+                        "spectators": { "type": "integer", "readOnly": true },
+                        "computer_move_kind": {
+                            "type": "string",
+                            "enum": ["random", "win", "block", "minimax"],
+                            "description": "How the computer chose its reply, only in the response to a move",
+                            "readOnly": true,
+                        },
+                        "is_new": {
+                            "type": "boolean",
+                            "description": "Whether the computer opened the game, only in the response to a creation request",
+                            "readOnly": true,
+                        },
+                        "history": { "type": "array", "items": schema_ref("Move"), "readOnly": true },
+                        "created_at": { "type": "integer", "description": "Unix time of creation", "readOnly": true },
+                        "updated_at": { "type": "integer", "description": "Unix time of the last change", "readOnly": true },
+                        "grid": {
+                            "type": "array",
+                            "items": { "type": "array", "items": { "type": "string" } },
+                            "readOnly": true,
+                        },
+                    },
+                },
+                "Move": {
+                    "type": "object",
+                    "properties": {
+                        "index": { "type": "integer", "minimum": 0, "maximum": 8 },
+                        "sign": { "type": "string", "enum": ["X", "O"] },
+                        "by": { "type": "string", "enum": ["PLAYER", "COMPUTER"] },
+                    },
+                },
+                "NewGame": {
+                    "type": "object",
+                    "required": ["board"],
+                    "properties": {
+                        "board": { "type": "string", "minLength": 9, "maxLength": 9 },
+                        "autoplay": { "type": "boolean", "default": false },
+                        "resume": { "type": "boolean", "default": false },
+                        "allow_finished": { "type": "boolean", "default": false },
+                        "analysis": { "type": "boolean", "default": false },
+                        "seed": { "type": "integer", "format": "int64", "minimum": 0 },
+                        "strategy": { "type": "string", "enum": ["random", "block", "minimax"], "default": "random" },
+                        "sign": { "type": "string", "description": "Sign of the player on an empty board, in the configured marks" },
+                        "computer_x_bias": { "type": "number", "minimum": 0, "maximum": 1, "default": 0.5 },
+                        "pvp": { "type": "boolean", "default": false },
+                    },
+                },
+                "TreeNode": {
+                    "type": "object",
+                    "properties": {
+                        "board": { "type": "string" },
+                        "index": { "type": "integer", "minimum": 0, "maximum": 8, "nullable": true },
+                        "to_move": { "type": "string", "enum": ["X", "O"] },
+                        "score": { "type": "integer", "minimum": -10, "maximum": 10 },
+                        "children": { "type": "array", "items": schema_ref("TreeNode") },
+                    },
+                },
+                "Tournament": {
+                    "type": "object",
+                    "required": ["games", "x", "o"],
+                    "properties": {
+                        "games": { "type": "integer", "minimum": 0, "maximum": MAX_TOURNAMENT_GAMES },
+                        "x": { "type": "string", "description": "Name of a registered strategy, e.g. random, block or minimax" },
+                        "o": { "type": "string", "description": "Name of a registered strategy, e.g. random, block or minimax" },
+                    },
+                },
+                "TournamentResult": {
+                    "type": "object",
+                    "properties": {
+                        "games": { "type": "integer" },
+                        "x_wins": { "type": "integer" },
+                        "o_wins": { "type": "integer" },
+                        "draws": { "type": "integer" },
+                    },
+                },
+                "ConvertRequest": {
+                    "type": "object",
+                    "required": ["board", "from", "to"],
+                    "properties": {
+                        "board": { "type": "string" },
+                        "from": { "type": "string", "enum": ["flat", "csv", "rows", "bitboard"] },
+                        "to": { "type": "string", "enum": ["flat", "csv", "rows", "bitboard"] },
+                    },
+                },
+                "MoveCheck": {
+                    "type": "object",
+                    "required": ["board"],
+                    "properties": {
+                        "board": { "type": "string" },
+                        "new_board": { "type": "string" },
+                        "index": { "type": "integer", "minimum": 0, "maximum": 8 },
+                    },
+                },
+                "Error": {
+                    "type": "object",
+                    "properties": { "error": { "type": "string" } },
+                },
+            },
+        },
+    })
+}
+
+/// Path parameter for the ID of a game
+fn id_parameter() -> Value {
+    json!({ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } })
+}
+
+/// Reference to a schema in the components section
+///
+/// # Arguments
+///
+/// * 'name' - Name of the schema
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+/// Array of a schema in the components section
+///
+/// # Arguments
+///
+/// * 'name' - Name of the schema of the items
+fn schema_array(name: &str) -> Value {
+    json!({ "type": "array", "items": schema_ref(name) })
+}
+
+/// JSON request body of a schema in the components section
+///
+/// # Arguments
+///
+/// * 'name' - Name of the schema
+fn json_body(name: &str) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": schema_ref(name) } } })
+}
+
+/// JSON response with a description and a schema
+///
+/// # Arguments
+///
+/// * 'description' - Description of the response
+///
+/// * 'schema' - Schema of the response body
+fn response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": schema } } })
+}
+
+/// Created response with the URL of the new resource in both the body and the Location header
+///
+/// # Arguments
+///
+/// * 'description' - Description of the response
+fn created_response(description: &str) -> Value {
+    let mut created = response(description, json!({ "type": "string", "format": "uri" }));
+    created["headers"] = json!({ "Location": { "schema": { "type": "string", "format": "uri" } } });
+    created
+}
+
+/// Error response with the Error schema as its body
+///
+/// # Arguments
+///
+/// * 'description' - Description of the error
+fn error_response(description: &str) -> Value {
+    response(description, schema_ref("Error"))
+}
+
+/// OPTIONS operation answering with the supported methods in the Allow header
+fn allow_operation() -> Value {
+    json!({
+        "summary": "List the supported methods",
+        "responses": {
+            "204": {
+                "description": "Supported methods in the Allow header",
+                "headers": { "Allow": { "schema": { "type": "string" } } },
+            },
+        },
+    })
+}
+
+/// Operation returning JSON on success and 404 for unknown games
+///
+/// # Arguments
+///
+/// * 'summary' - Summary of the operation
+///
+/// * 'description' - Description of the successful response
+///
+/// * 'schema' - Schema of the successful response body
+fn operation(summary: &str, description: &str, schema: Value) -> Value {
+    json!({
+        "summary": summary,
+        "responses": {
+            "200": response(description, schema),
+            "404": { "description": "Game not found" },
+        },
+    })
+}
+
+/// Operation returning a non JSON body on success and 404 for unknown games
+///
+/// # Arguments
+///
+/// * 'summary' - Summary of the operation
+///
+/// * 'media_type' - Content type of the successful response
+fn text_operation(summary: &str, media_type: &str) -> Value {
+    json!({
+        "summary": summary,
+        "responses": {
+            "200": { "description": summary, "content": { media_type: { "schema": { "type": "string" } } } },
+            "404": { "description": "Game not found" },
+        },
+    })
+}
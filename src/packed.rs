@@ -0,0 +1,144 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Signs of the cells by their 2 bit code, the fourth code is unused
+const CODES: [char; 3] = ['-', 'X', 'O'];
+
+/// Amount of cells packed into a byte
+const CELLS_PER_BYTE: usize = 4;
+
+/// Board as it's stored in a game, with 2 bits per cell packed 4 cells to a byte.
+///
+/// Only boards made of '-', 'X' and 'O' are packed. Anything else, such as a board submitted by a client
+/// that has yet to be normalized and validated, is kept as it is, so packing never loses a character.
+/// Serialized as the board string, which keeps the wire format unchanged.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackedBoard {
+    /// Cells packed 4 to a byte, the first cell in the lowest bits
+    Packed {
+        /// Amount of cells on the board
+        cells: usize,
+        /// The packed cells
+        bytes: Box<[u8]>,
+    },
+    /// Board with characters other than the internal signs and the empty cell
+    Raw(String),
+}
+
+impl PackedBoard {
+    /// Packs the board, keeping it as it is if it has characters that can't be packed
+    ///
+    /// # Arguments
+    ///
+    /// * 'board' - Representation of the board
+    pub fn pack(board: &str) -> PackedBoard {
+        let cells = board.chars().count();
+        let mut bytes = vec![0u8; cells.div_ceil(CELLS_PER_BYTE)];
+        for (index, cell) in board.chars().enumerate() {
+            let code = match CODES.iter().position(|code| *code == cell) {
+                Some(code) => code as u8,
+                None => return PackedBoard::Raw(String::from(board)),
+            };
+            bytes[index / CELLS_PER_BYTE] |= code << (2 * (index % CELLS_PER_BYTE));
+        }
+        PackedBoard::Packed {
+            cells,
+            bytes: bytes.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the board as a string
+    pub fn unpack(&self) -> String {
+        match self {
+            PackedBoard::Packed { cells, bytes } => (0..*cells)
+                .map(|index| {
+                    let code = bytes[index / CELLS_PER_BYTE] >> (2 * (index % CELLS_PER_BYTE));
+                    CODES[(code & 0b11) as usize]
+                })
+                .collect(),
+            PackedBoard::Raw(board) => board.clone(),
+        }
+    }
+
+    /// Returns the amount of memory allocated for the board in bytes
+    pub fn heap_size(&self) -> usize {
+        match self {
+            PackedBoard::Packed { bytes, .. } => bytes.len(),
+            PackedBoard::Raw(board) => board.capacity(),
+        }
+    }
+}
+
+impl Default for PackedBoard {
+    fn default() -> PackedBoard {
+        PackedBoard::Raw(String::new())
+    }
+}
+
+impl Serialize for PackedBoard {
+    /// Serializes the board as a string
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.unpack())
+    }
+}
+
+impl<'de> Deserialize<'de> for PackedBoard {
+    /// Deserializes the board from a string, packing it if possible
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PackedBoard, D::Error> {
+        String::deserialize(deserializer).map(|board| PackedBoard::pack(&board))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_of_any_size_round_trip() {
+        let boards = [
+            "",
+            "X",
+            "XO-",
+            "XO-X",
+            "XO-XO",
+            "X-O-X-O-X",
+            "XXXXOOOO----",
+            "XO--XO--XO--XO--",
+            "-X-O-X-O-X-O-X-O-X-O-X-O-",
+        ];
+
+        for board in boards {
+            let packed = PackedBoard::pack(board);
+            assert!(matches!(packed, PackedBoard::Packed { .. }), "{}", board);
+            assert_eq!(packed.unpack(), board);
+        }
+    }
+
+    #[test]
+    fn packed_boards_take_a_quarter_of_the_cells() {
+        let board = "X".repeat(100);
+
+        let packed = PackedBoard::pack(&board);
+
+        assert_eq!(packed.heap_size(), 25);
+        assert_eq!(PackedBoard::pack("X-O-X-O-X").heap_size(), 3);
+    }
+
+    #[test]
+    fn boards_with_other_characters_are_kept_as_they_are() {
+        for board in ["x-o------", " X-------", "XX--Z----", "X_O______"] {
+            let packed = PackedBoard::pack(board);
+            assert_eq!(packed, PackedBoard::Raw(String::from(board)));
+            assert_eq!(packed.unpack(), board);
+        }
+    }
+
+    #[test]
+    fn board_is_serialized_as_the_string() {
+        let packed = PackedBoard::pack("X---O----");
+
+        let json = rocket::serde::json::to_string(&packed).unwrap();
+        assert_eq!(json, r#""X---O----""#);
+        let read: PackedBoard = rocket::serde::json::from_str(&json).unwrap();
+        assert_eq!(read, packed);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One of the two signs placed on the board
+///
+/// The wire format of the board is unchanged, signs are converted to and from single characters
+/// at the boundaries.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Sign {
+    X,
+    O,
+}
+
+impl Sign {
+    /// Returns the other sign
+    pub fn opponent(self) -> Sign {
+        match self {
+            Sign::X => Sign::O,
+            Sign::O => Sign::X,
+        }
+    }
+
+    /// Returns the sign as the character used on the board
+    pub fn as_char(self) -> char {
+        match self {
+            Sign::X => 'X',
+            Sign::O => 'O',
+        }
+    }
+
+    /// Returns the sign as a string slice
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Sign::X => "X",
+            Sign::O => "O",
+        }
+    }
+}
+
+impl TryFrom<char> for Sign {
+    type Error = char;
+
+    /// Converts a board character to a sign, failing with the character if it's not a sign
+    fn try_from(char: char) -> Result<Sign, char> {
+        match char {
+            'X' => Ok(Sign::X),
+            'O' => Ok(Sign::O),
+            _ => Err(char),
+        }
+    }
+}
+
+impl TryFrom<&str> for Sign {
+    type Error = ();
+
+    /// Converts "X" or "O" to a sign
+    fn try_from(string: &str) -> Result<Sign, ()> {
+        match string {
+            "X" => Ok(Sign::X),
+            "O" => Ok(Sign::O),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Sign> for char {
+    fn from(sign: Sign) -> char {
+        sign.as_char()
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_convert_to_and_from_characters() {
+        for sign in [Sign::X, Sign::O] {
+            assert_eq!(Sign::try_from(sign.as_char()), Ok(sign));
+            assert_eq!(Sign::try_from(sign.as_str()), Ok(sign));
+            assert_eq!(char::from(sign).to_string(), sign.to_string());
+        }
+        assert_eq!(Sign::X.as_char(), 'X');
+        assert_eq!(Sign::O.as_str(), "O");
+    }
+
+    #[test]
+    fn other_characters_are_not_signs() {
+        assert_eq!(Sign::try_from('-'), Err('-'));
+        assert_eq!(Sign::try_from('x'), Err('x'));
+        assert_eq!(Sign::try_from("XO"), Err(()));
+        assert_eq!(Sign::try_from(""), Err(()));
+    }
+
+    #[test]
+    fn opponent_is_the_other_sign() {
+        assert_eq!(Sign::X.opponent(), Sign::O);
+        assert_eq!(Sign::O.opponent(), Sign::X);
+    }
+}
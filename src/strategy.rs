@@ -0,0 +1,237 @@
+use crate::ai::best_moves;
+use crate::board::{empty_indices, place, winner};
+use crate::sign::Sign;
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Name of the strategy games use when none is chosen
+pub const DEFAULT_STRATEGY: &str = "random";
+
+/// A way for the computer to choose its moves
+///
+/// Implementations are registered by name in Strategies and games look theirs up by that name.
+pub trait MoveStrategy: Send + Sync {
+    /// Chooses the cell the computer plays
+    ///
+    /// Returns the index of an empty cell along with how it was chosen: "random", "win", "block" or
+    /// "minimax"
+    ///
+    /// # Arguments
+    ///
+    /// * 'board' - Representation of the board, with at least one empty cell and no completed line
+    ///
+    /// * 'sign' - Sign the computer places
+    ///
+    /// * 'rng' - Random number generator of the game, any random choices must be made with it so
+    ///   seeded games can be replayed
+    fn choose(&self, board: &str, sign: Sign, rng: &mut StdRng) -> (usize, &'static str);
+}
+
+/// Plays a random empty cell
+pub struct Random;
+
+impl MoveStrategy for Random {
+    fn choose(&self, board: &str, _sign: Sign, rng: &mut StdRng) -> (usize, &'static str) {
+        let empty_spaces = empty_indices(board);
+        (empty_spaces[rng.gen_range(0..empty_spaces.len())], "random")
+    }
+}
+
+/// Completes its own line if it can, otherwise blocks the opponent's line, otherwise plays randomly
+pub struct Block;
+
+impl MoveStrategy for Block {
+    fn choose(&self, board: &str, sign: Sign, rng: &mut StdRng) -> (usize, &'static str) {
+        for (side, kind) in [(sign, "win"), (sign.opponent(), "block")] {
+            let completing = empty_indices(board)
+                .into_iter()
+                .find(|index| winner(&place(board, *index, side)) == Some(side));
+            if let Some(index) = completing {
+                return (index, kind);
+            }
+        }
+        Random.choose(board, sign, rng)
+    }
+}
+
+/// How minimax picks between moves with equally good scores
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TieBreak {
+    /// The move in the cell with the lowest index
+    #[default]
+    First,
+
+    /// A random one of the moves
+    Random,
+
+    /// The center first, then the corners and then the edges, by index within each
+    Positional,
+}
+
+impl TieBreak {
+    /// Picks one of the equally good moves
+    ///
+    /// # Arguments
+    ///
+    /// * 'moves' - Indexes of the cells of the moves in order, at least one
+    ///
+    /// * 'rng' - Random number generator of the game
+    fn pick(&self, moves: &[usize], rng: &mut StdRng) -> usize {
+        match self {
+            TieBreak::First => moves[0],
+            TieBreak::Random => moves[rng.gen_range(0..moves.len())],
+            TieBreak::Positional => *moves
+                .iter()
+                .min_by_key(|index| match index {
+                    4 => 0,
+                    0 | 2 | 6 | 8 => 1,
+                    _ => 2,
+                })
+                .unwrap(), // At least one move
+        }
+    }
+}
+
+impl FromStr for TieBreak {
+    type Err = ();
+
+    /// Converts a tie-break as it's configured, e.g. "positional"
+    fn from_str(string: &str) -> Result<TieBreak, ()> {
+        match string {
+            "first" => Ok(TieBreak::First),
+            "random" => Ok(TieBreak::Random),
+            "positional" => Ok(TieBreak::Positional),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Plays the best move found by minimax
+///
+/// The best moves are cached by position since the same positions come up in game after game and
+/// searching from an empty board is expensive. There are only a few thousand positions.
+pub struct Minimax {
+    /// How the move is picked when several are equally good
+    tie_break: TieBreak,
+
+    /// Best moves already found, keyed by board and the sign to move
+    cache: Mutex<HashMap<(String, Sign), Vec<usize>>>,
+}
+
+impl Minimax {
+    /// Creates the strategy with an empty cache
+    ///
+    /// # Arguments
+    ///
+    /// * 'tie_break' - How the move is picked when several are equally good
+    pub fn new(tie_break: TieBreak) -> Minimax {
+        Minimax {
+            tie_break,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl MoveStrategy for Minimax {
+    fn choose(&self, board: &str, sign: Sign, rng: &mut StdRng) -> (usize, &'static str) {
+        let key = (String::from(board), sign);
+        let cached = self.cache.lock().unwrap().get(&key).cloned();
+        // Searched without holding the lock, concurrent searches of the same position find the same moves
+        let moves = cached.unwrap_or_else(|| {
+            let moves = best_moves(board, sign);
+            self.cache.lock().unwrap().insert(key, moves.clone());
+            moves
+        });
+        assert!(
+            !moves.is_empty(),
+            "board has an empty cell and no completed line"
+        );
+        (self.tie_break.pick(&moves, rng), "minimax")
+    }
+}
+
+/// Registry of the strategies games can be configured with, kept in the Rocket state
+///
+/// Cheap to clone, so work running off the async workers can take its own copy.
+#[derive(Clone)]
+pub struct Strategies {
+    /// Strategies by name
+    strategies: HashMap<&'static str, Arc<dyn MoveStrategy>>,
+}
+
+impl Strategies {
+    /// Creates a registry with the built-in random, block and minimax strategies
+    ///
+    /// # Arguments
+    ///
+    /// * 'tie_break' - How the minimax strategy picks between equally good moves
+    pub fn new(tie_break: TieBreak) -> Strategies {
+        let mut strategies = Strategies {
+            strategies: HashMap::new(),
+        };
+        strategies.register(DEFAULT_STRATEGY, Box::new(Random));
+        strategies.register("block", Box::new(Block));
+        strategies.register("minimax", Box::new(Minimax::new(tie_break)));
+        strategies
+    }
+
+    /// Registers a strategy, replacing any strategy already registered with the name
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - Name games choose the strategy by
+    ///
+    /// * 'strategy' - The strategy
+    pub fn register(&mut self, name: &'static str, strategy: Box<dyn MoveStrategy>) {
+        self.strategies.insert(name, Arc::from(strategy));
+    }
+
+    /// Looks up a strategy by name
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - Name the strategy was registered with
+    pub fn get(&self, name: &str) -> Option<&dyn MoveStrategy> {
+        self.strategies.get(name).map(|strategy| strategy.as_ref())
+    }
+}
+
+impl Default for Strategies {
+    fn default() -> Strategies {
+        Strategies::new(TieBreak::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn positional_tie_break_prefers_the_center_then_the_corners() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(TieBreak::Positional.pick(&[0, 1, 4, 8], &mut rng), 4);
+        assert_eq!(TieBreak::Positional.pick(&[1, 3, 6, 8], &mut rng), 6);
+        assert_eq!(TieBreak::First.pick(&[0, 1, 4, 8], &mut rng), 0);
+    }
+
+    #[test]
+    fn positional_minimax_opens_in_the_center() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Every opening draws with perfect play, so all nine moves are equally good
+        let positional = Minimax::new(TieBreak::Positional);
+        assert_eq!(
+            positional.choose("---------", Sign::X, &mut rng),
+            (4, "minimax")
+        );
+        let first = Minimax::new(TieBreak::First);
+        assert_eq!(first.choose("---------", Sign::X, &mut rng), (0, "minimax"));
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Keeps a bounded record of recently deleted game IDs.
+///
+/// Lets requests for deleted games be answered with 410 Gone rather than 404 Not Found.
+/// Entries are forgotten once they are older than the TTL or when the capacity is exceeded,
+/// oldest first.
+pub struct Tombstones {
+    /// Deleted game IDs and the time of deletion, oldest first
+    pub deleted: Mutex<VecDeque<(String, Instant)>>,
+
+    /// Maximum amount of remembered IDs
+    pub capacity: usize,
+
+    /// How long an ID is remembered after deletion
+    pub ttl: Duration,
+}
+
+impl Tombstones {
+    /// Creates an empty record of deleted games
+    ///
+    /// # Arguments
+    ///
+    /// * 'capacity' - Maximum amount of remembered IDs
+    ///
+    /// * 'ttl' - How long an ID is remembered after deletion in seconds
+    pub fn new(capacity: usize, ttl: u64) -> Tombstones {
+        Tombstones {
+            deleted: Mutex::new(VecDeque::new()),
+            capacity,
+            ttl: Duration::from_secs(ttl),
+        }
+    }
+
+    /// Records the deletion of a game
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the deleted game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn bury(&self, game_id: &str) {
+        let mut deleted = self.deleted.lock().unwrap();
+        self.prune(&mut deleted);
+        if self.capacity == 0 {
+            return;
+        }
+        while deleted.len() >= self.capacity {
+            deleted.pop_front();
+        }
+        deleted.push_back((String::from(game_id), Instant::now()));
+    }
+
+    /// Returns whether the game was deleted within the TTL
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn is_buried(&self, game_id: &str) -> bool {
+        let mut deleted = self.deleted.lock().unwrap();
+        self.prune(&mut deleted);
+        deleted.iter().any(|(id, _)| id == game_id)
+    }
+
+    /// Forgets IDs older than the TTL
+    ///
+    /// # Arguments
+    ///
+    /// * 'deleted' - The locked record of deleted games
+    fn prune(&self, deleted: &mut VecDeque<(String, Instant)>) {
+        while let Some((_, time)) = deleted.front() {
+            if time.elapsed() < self.ttl {
+                break;
+            }
+            deleted.pop_front();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::board::{empty_indices, place, winner};
+use crate::game::CreateError;
+use crate::sign::Sign;
+use crate::strategy::{MoveStrategy, Strategies};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// Maximum amount of games played in a single tournament
+pub const MAX_TOURNAMENT_GAMES: u32 = 100;
+
+/// Tournament settings as sent in the POST request payload
+#[derive(Deserialize, Debug)]
+pub struct Tournament {
+    /// Amount of games to play, capped to MAX_TOURNAMENT_GAMES
+    pub games: u32,
+
+    /// Name of the strategy playing X, X always moves first
+    pub x: String,
+
+    /// Name of the strategy playing O
+    pub o: String,
+}
+
+/// Aggregate results of a tournament
+#[derive(Serialize, Default, PartialEq, Debug)]
+pub struct TournamentResult {
+    pub games: u32,
+    pub x_wins: u32,
+    pub o_wins: u32,
+    pub draws: u32,
+}
+
+impl Tournament {
+    /// Plays the tournament without storing any of the games
+    ///
+    /// Both sides choose their moves with strategies from the registry, the same way the computer does
+    /// in regular games.
+    ///
+    /// Returns the aggregate results, the totals always sum up to the amount of games played, or an error
+    /// if no strategy is registered with either name
+    ///
+    /// # Arguments
+    ///
+    /// * 'strategies' - Registry of the strategies the sides can choose their moves with
+    pub fn play(&self, strategies: &Strategies) -> Result<TournamentResult, CreateError> {
+        let lookup = |name: &String| {
+            strategies
+                .get(name)
+                .ok_or_else(|| CreateError::UnknownStrategy(name.clone()))
+        };
+        let (x, o) = (lookup(&self.x)?, lookup(&self.o)?);
+
+        let mut rng = StdRng::from_entropy();
+        let mut result = TournamentResult {
+            games: self.games.min(MAX_TOURNAMENT_GAMES),
+            ..Default::default()
+        };
+        for _ in 0..result.games {
+            match play_game(x, o, &mut rng) {
+                Some(Sign::X) => result.x_wins += 1,
+                Some(Sign::O) => result.o_wins += 1,
+                None => result.draws += 1,
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Plays a single game from an empty board
+///
+/// Returns the sign of the winner, or None for a draw
+///
+/// # Arguments
+///
+/// * 'x' - Strategy playing X
+///
+/// * 'o' - Strategy playing O
+///
+/// * 'rng' - Random number generator the strategies make their random choices with
+fn play_game(x: &dyn MoveStrategy, o: &dyn MoveStrategy, rng: &mut StdRng) -> Option<Sign> {
+    let mut board = String::from("---------");
+    let mut sign = Sign::X;
+    while winner(&board).is_none() && !empty_indices(&board).is_empty() {
+        let strategy = if sign == Sign::X { x } else { o };
+        let (index, _) = strategy.choose(&board, sign, rng);
+        board = place(&board, index, sign);
+        sign = sign.opponent();
+    }
+    winner(&board)
+}
@@ -0,0 +1,64 @@
+use crate::config::Config;
+use crate::game::{Game, Move};
+use log::{info, warn};
+use rocket::serde::json;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// Complete record of a finished game for later analysis
+#[derive(Serialize)]
+struct Transcript<'a> {
+    /// ID of the game
+    id: &'a str,
+    /// Every sign placed during the game, oldest first
+    moves: &'a [Move],
+    /// Final status of the game
+    status: &'a str,
+    /// Side that ended the game
+    ended_by: Option<&'a str>,
+}
+
+/// Writes the transcript of a finished game to the configured sinks as a single JSON line
+///
+/// Does nothing for running games or when no sink is configured. Failing to write the transcript
+/// file is logged but doesn't fail the request.
+///
+/// # Arguments
+///
+/// * 'game' - The game that might have just finished
+///
+/// * 'config' - Runtime configuration of the API
+pub fn record(game: &Game, config: &Config) {
+    if !game.is_over() || (!config.transcript_log && config.transcript_file.is_none()) {
+        return;
+    }
+
+    let transcript = Transcript {
+        id: game.get_id().as_deref().unwrap_or_default(),
+        moves: game.get_history(),
+        status: game.get_status().as_deref().unwrap_or_default(),
+        ended_by: game.get_ended_by().as_deref(),
+    };
+    let line = match json::to_string(&transcript) {
+        Ok(line) => line,
+        Err(e) => {
+            warn!("Unable to serialize transcript: {}", e);
+            return;
+        }
+    };
+
+    if config.transcript_log {
+        info!("Game transcript: {}", line);
+    }
+    if let Some(path) = &config.transcript_file {
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            warn!("Unable to write transcript to {}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use rocket::tokio::sync::Notify;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Change notifier of a single game along with the amount of clients streaming it
+pub struct Channel {
+    /// Woken up every time the game changes
+    pub notify: Notify,
+
+    /// Amount of connected event streams
+    pub spectators: AtomicU32,
+}
+
+/// A connected event stream, counted as a spectator of the game for as long as it's alive
+pub struct Spectator {
+    /// Channel of the watched game
+    pub channel: Arc<Channel>,
+}
+
+impl Drop for Spectator {
+    /// Stops counting the stream once it disconnects
+    fn drop(&mut self) {
+        self.channel.spectators.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Container for a HashMap of change notifiers and spectator counts for each game by ID.
+///
+/// Handlers that change a game notify its waiters, which lets long-polling requests return as soon
+/// as the game changes. The HashMap is wrapped in a Mutex to allow it to be handled asynchronously,
+/// which is shared through an Arc so background tasks can notify the same waiters as the handlers.
+#[derive(Clone)]
+pub struct Watchers {
+    pub notifiers: Arc<Mutex<HashMap<String, Arc<Channel>>>>,
+}
+
+impl Watchers {
+    /// Returns the channel of the game, creating it if nobody has waited on the game before
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn subscribe(&self, game_id: &str) -> Arc<Channel> {
+        self.notifiers
+            .lock()
+            .unwrap()
+            .entry(String::from(game_id))
+            .or_insert_with(|| {
+                Arc::new(Channel {
+                    notify: Notify::new(),
+                    spectators: AtomicU32::new(0),
+                })
+            })
+            .clone()
+    }
+
+    /// Subscribes an event stream to the game and counts it as a spectator until it's dropped
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn spectate(&self, game_id: &str) -> Spectator {
+        let channel = self.subscribe(game_id);
+        channel.spectators.fetch_add(1, Ordering::SeqCst);
+        Spectator { channel }
+    }
+
+    /// Returns the amount of event streams connected to the game
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn spectators(&self, game_id: &str) -> u32 {
+        match self.notifiers.lock().unwrap().get(game_id) {
+            Some(channel) => channel.spectators.load(Ordering::SeqCst),
+            None => 0,
+        }
+    }
+
+    /// Wakes up everyone waiting for the game to change
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the game that changed
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn notify(&self, game_id: &str) {
+        if let Some(channel) = self.notifiers.lock().unwrap().get(game_id) {
+            channel.notify.notify_waiters();
+        }
+    }
+
+    /// Wakes up everyone waiting for the game and forgets its notifier, used when the game is deleted
+    ///
+    /// # Arguments
+    ///
+    /// * 'game_id' - ID of the deleted game
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn remove(&self, game_id: &str) {
+        if let Some(channel) = self.notifiers.lock().unwrap().remove(game_id) {
+            channel.notify.notify_waiters();
+        }
+    }
+
+    /// Wakes up everyone waiting for any game and forgets all notifiers, used when the store is reset
+    ///
+    /// # Panics
+    /// May panic if the the function is unable to open up the mutex
+    pub fn clear(&self) {
+        for (_, channel) in self.notifiers.lock().unwrap().drain() {
+            channel.notify.notify_waiters();
+        }
+    }
+}